@@ -0,0 +1,43 @@
+//! Compares the speed of the different inner loops for estimate_pi_fast.
+//!
+//! Run with `cargo bench`. Each kernel is timed over the same number of
+//! samples, alongside the original estimate_pi_fast which uses the
+//! thread-local generator.
+
+use std::time::{Duration, Instant};
+
+use first_library::{estimate_pi_fast, estimate_pi_fast_kernel, Kernel};
+
+const SAMPLES: u64 = 50_000_000;
+
+fn main() {
+    report("estimate_pi_fast", time(|| estimate_pi_fast(SAMPLES)));
+
+    for &kernel in Kernel::ALL.iter() {
+        let name = format!("kernel {}", kernel.name());
+        report(&name, time(|| estimate_pi_fast_kernel(SAMPLES, kernel, 1).pi()));
+    }
+}
+
+/// Run `estimate` once to warm up, then return the best of three timings.
+fn time<F: FnMut() -> f64>(mut estimate: F) -> (f64, Duration) {
+    let mut pi = estimate();
+    let mut best = Duration::MAX;
+    for _ in 0..3 {
+        let start = Instant::now();
+        pi = estimate();
+        best = best.min(start.elapsed());
+    }
+    (pi, best)
+}
+
+fn report(name: &str, (pi, duration): (f64, Duration)) {
+    let rate = SAMPLES as f64 / duration.as_secs_f64() / 1e6;
+    println!(
+        "{:<20} {:>8.1} ms  {:>8.1} M samples/s  pi = {}",
+        name,
+        duration.as_secs_f64() * 1e3,
+        rate,
+        pi
+    );
+}
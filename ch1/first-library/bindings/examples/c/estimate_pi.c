@@ -0,0 +1,41 @@
+/*
+ * Calling first-library from C.
+ *
+ * Build the library first with `cargo build -p first-library-bindings`,
+ * then from this directory:
+ *
+ *     cc estimate_pi.c -I../../include -L../../../target/debug \
+ *         -lfirst_library_bindings -Wl,-rpath,../../../target/debug -o estimate_pi
+ *     ./estimate_pi
+ */
+
+#include <inttypes.h>
+#include <stdio.h>
+
+#include "first_library.h"
+
+int main(void) {
+    /* A one-off estimate */
+    printf("fast: %f\n", fl_estimate_pi_fast_seeded(1000000, 1));
+
+    /* The same counts on every platform */
+    fl_estimate deterministic = fl_estimate_pi_deterministic(1000000, 2024);
+    printf("deterministic: %" PRIu64 " hits from %" PRIu64 " samples, pi = %f\n",
+           deterministic.hits, deterministic.samples, fl_estimate_pi(deterministic));
+
+    /* Two estimators built up separately, e.g. in different threads, and
+     * then combined */
+    fl_pi_estimator *first = fl_pi_estimator_new(1);
+    fl_pi_estimator *second = fl_pi_estimator_new(2);
+    fl_pi_estimator_add_samples(first, 500000);
+    fl_pi_estimator_add_samples(second, 500000);
+    fl_pi_estimator_merge(first, second);
+
+    fl_estimate merged = fl_pi_estimator_estimate(first);
+    printf("merged: %" PRIu64 " samples, pi = %f +/- %f\n",
+           merged.samples, fl_pi_estimator_pi(first), fl_estimate_standard_error(merged));
+
+    fl_pi_estimator_free(first);
+    fl_pi_estimator_free(second);
+    return 0;
+}
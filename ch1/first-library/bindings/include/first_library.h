@@ -0,0 +1,100 @@
+/*
+ * C interface to first-library, the Monte Carlo estimators of pi.
+ *
+ * Build the library with `cargo build --release -p first-library-bindings`,
+ * which produces target/release/libfirst_library_bindings.so (.dylib on
+ * macOS, .dll on Windows), and link against it with -lfirst_library_bindings.
+ *
+ * Every function here is defined in src/ffi.rs.
+ *
+ * The same functions are exported from the WebAssembly module, but a
+ * WebAssembly function can only take and return plain numbers. Under the
+ * C calling convention for wasm32, a function returning an fl_estimate
+ * instead takes a pointer to memory in the module to write it to, as an
+ * extra first argument, and an fl_estimate argument is passed as a pointer
+ * to a copy of it in the module's memory. Hosts which don't want to manage
+ * the module's memory can use the _hits versions of the estimators, which
+ * return only a number, and fl_pi_estimator_pi.
+ */
+
+#ifndef FIRST_LIBRARY_H
+#define FIRST_LIBRARY_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The counts from an estimate: pi is estimated as 4 * hits / samples. */
+typedef struct fl_estimate {
+    uint64_t hits;
+    uint64_t samples;
+} fl_estimate;
+
+/* The kernels fl_estimate_pi_kernel can use. */
+#define FL_KERNEL_STANDARD 0
+#define FL_KERNEL_PCG 1
+#define FL_KERNEL_BATCHED 2
+#define FL_KERNEL_DETERMINISTIC 3
+
+/* Estimate pi with `samples` samples from a generator seeded with `seed`. */
+double fl_estimate_pi_fast_seeded(uint64_t samples, uint64_t seed);
+
+/* Estimate pi using one of the FL_KERNEL_ kernels. An unknown kernel gives
+ * an estimate with no samples. */
+fl_estimate fl_estimate_pi_kernel(uint64_t samples, uint32_t kernel, uint64_t seed);
+
+/* The number of hits from fl_estimate_pi_kernel, which took `samples`
+ * samples unless the kernel was unknown, when it gives 0. */
+uint64_t fl_estimate_pi_kernel_hits(uint64_t samples, uint32_t kernel, uint64_t seed);
+
+/* Estimate pi giving the same counts on every platform for the same seed. */
+fl_estimate fl_estimate_pi_deterministic(uint64_t samples, uint64_t seed);
+
+/* The number of hits from fl_estimate_pi_deterministic. */
+uint64_t fl_estimate_pi_deterministic_hits(uint64_t samples, uint64_t seed);
+
+/* Estimate pi with the samples shared between `threads` threads, or one
+ * thread per CPU if `threads` is 0. */
+fl_estimate fl_estimate_pi_parallel(uint64_t samples, uint32_t threads, uint64_t seed);
+
+/* The number of hits from fl_estimate_pi_parallel. */
+uint64_t fl_estimate_pi_parallel_hits(uint64_t samples, uint32_t threads, uint64_t seed);
+
+/* The estimate of pi from an fl_estimate's counts (NaN if it has none). */
+double fl_estimate_pi(fl_estimate estimate);
+
+/* The standard error of an fl_estimate. */
+double fl_estimate_standard_error(fl_estimate estimate);
+
+/* An estimate of pi which can be built up a bit at a time, and merged
+ * with estimates made elsewhere. */
+typedef struct fl_pi_estimator fl_pi_estimator;
+
+/* Create an estimator seeded with `seed`. Free it with fl_pi_estimator_free. */
+fl_pi_estimator *fl_pi_estimator_new(uint64_t seed);
+
+/* Take `samples` more samples. */
+void fl_pi_estimator_add_samples(fl_pi_estimator *estimator, uint64_t samples);
+
+/* Add the counts of `other` to `estimator`. */
+void fl_pi_estimator_merge(fl_pi_estimator *estimator, const fl_pi_estimator *other);
+
+/* Add counts made elsewhere, e.g. in another process, to `estimator`. */
+void fl_pi_estimator_merge_estimate(fl_pi_estimator *estimator, fl_estimate other);
+
+/* The counts so far. */
+fl_estimate fl_pi_estimator_estimate(const fl_pi_estimator *estimator);
+
+/* The current estimate of pi, or NaN if there are no samples yet. */
+double fl_pi_estimator_pi(const fl_pi_estimator *estimator);
+
+/* Free an estimator. Passing NULL does nothing. */
+void fl_pi_estimator_free(fl_pi_estimator *estimator);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FIRST_LIBRARY_H */
@@ -0,0 +1,182 @@
+// A C interface to the estimators, so that they can be called from C and
+// C++, or from any WebAssembly host through the module's plain exports.
+// `include/first_library.h` declares everything here for C, and
+// `examples/c/estimate_pi.c` shows it being used.
+//
+// Every function is `extern "C"` and `#[no_mangle]`, so it can be found by
+// its plain name. Results come back as an `Estimate`, which is laid out
+// like the C struct `fl_estimate`. A `PiEstimator` is handed out as a
+// pointer which C treats as opaque, and must be given back to
+// `fl_pi_estimator_free` when it is finished with.
+//
+// A WebAssembly function can only take and return plain numbers, so a
+// struct such as `fl_estimate` goes through a pointer into the module's
+// memory instead, which the host has to allocate and read itself. The
+// `_hits` versions of the estimators return just the number of hits, for
+// hosts which would rather not.
+
+use std::ptr;
+use std::thread;
+
+use first_library::{Estimate, Kernel, PiEstimator};
+
+/// Estimate pi with `samples` samples from a generator seeded with `seed`.
+#[no_mangle]
+pub extern "C" fn fl_estimate_pi_fast_seeded(samples: u64, seed: u64) -> f64 {
+    first_library::estimate_pi_fast_seeded(samples, seed)
+}
+
+/// Estimate pi with `samples` samples using one of the kernels, numbered
+/// as in the `Kernel` enum from 0. An unknown kernel gives an estimate
+/// with no samples, whose value is NaN.
+#[no_mangle]
+pub extern "C" fn fl_estimate_pi_kernel(samples: u64, kernel: u32, seed: u64) -> Estimate {
+    match Kernel::ALL.get(kernel as usize) {
+        Some(&kernel) => first_library::estimate_pi_fast_kernel(samples, kernel, seed),
+        None => Estimate::new(0, 0),
+    }
+}
+
+/// Version of fl_estimate_pi_kernel which returns only the number of hits.
+/// An unknown kernel takes no samples, and so gives 0.
+#[no_mangle]
+pub extern "C" fn fl_estimate_pi_kernel_hits(samples: u64, kernel: u32, seed: u64) -> u64 {
+    fl_estimate_pi_kernel(samples, kernel, seed).hits
+}
+
+/// Estimate pi with `samples` samples, giving the same counts on every
+/// platform for the same seed.
+#[no_mangle]
+pub extern "C" fn fl_estimate_pi_deterministic(samples: u64, seed: u64) -> Estimate {
+    first_library::estimate_pi_deterministic(samples, seed)
+}
+
+/// Version of fl_estimate_pi_deterministic which returns only the number
+/// of hits.
+#[no_mangle]
+pub extern "C" fn fl_estimate_pi_deterministic_hits(samples: u64, seed: u64) -> u64 {
+    fl_estimate_pi_deterministic(samples, seed).hits
+}
+
+/// Estimate pi with `samples` samples shared between `threads` threads,
+/// or one thread per CPU if `threads` is 0.
+#[no_mangle]
+pub extern "C" fn fl_estimate_pi_parallel(samples: u64, threads: u32, seed: u64) -> Estimate {
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        threads => threads as usize,
+    };
+    first_library::estimate_pi_parallel_detailed(samples, threads, seed)
+}
+
+/// Version of fl_estimate_pi_parallel which returns only the number of
+/// hits.
+#[no_mangle]
+pub extern "C" fn fl_estimate_pi_parallel_hits(samples: u64, threads: u32, seed: u64) -> u64 {
+    fl_estimate_pi_parallel(samples, threads, seed).hits
+}
+
+/// The estimate of pi from an `Estimate`'s counts.
+#[no_mangle]
+pub extern "C" fn fl_estimate_pi(estimate: Estimate) -> f64 {
+    estimate.pi()
+}
+
+/// The standard error of an `Estimate`.
+#[no_mangle]
+pub extern "C" fn fl_estimate_standard_error(estimate: Estimate) -> f64 {
+    estimate.standard_error()
+}
+
+/// Create a `PiEstimator` with a generator seeded with `seed`. Free it with
+/// `fl_pi_estimator_free`.
+#[no_mangle]
+pub extern "C" fn fl_pi_estimator_new(seed: u64) -> *mut PiEstimator {
+    Box::into_raw(Box::new(PiEstimator::seeded(seed)))
+}
+
+/// Take `samples` more samples. Does nothing if `estimator` is null.
+///
+/// # Safety
+///
+/// `estimator` must be null or a pointer from `fl_pi_estimator_new` which
+/// hasn't been freed, and not in use by another thread.
+#[no_mangle]
+pub unsafe extern "C" fn fl_pi_estimator_add_samples(estimator: *mut PiEstimator, samples: u64) {
+    if let Some(estimator) = estimator.as_mut() {
+        estimator.add_samples(samples);
+    }
+}
+
+/// Add the counts of `other` to `estimator`. Does nothing if either is
+/// null.
+///
+/// # Safety
+///
+/// Both must be null or pointers from `fl_pi_estimator_new` which haven't
+/// been freed. `estimator` must not be in use by another thread.
+#[no_mangle]
+pub unsafe extern "C" fn fl_pi_estimator_merge(estimator: *mut PiEstimator, other: *const PiEstimator) {
+    // Merging an estimator into itself would count its samples twice, but
+    // it must not create two references to it at once
+    if ptr::eq(estimator, other) {
+        if let Some(estimator) = estimator.as_mut() {
+            let counts = estimator.estimate();
+            estimator.merge_estimate(counts);
+        }
+    } else if let (Some(estimator), Some(other)) = (estimator.as_mut(), other.as_ref()) {
+        estimator.merge(other);
+    }
+}
+
+/// Add counts made elsewhere, e.g. in another process, to `estimator`.
+/// Does nothing if `estimator` is null.
+///
+/// # Safety
+///
+/// `estimator` must be null or a pointer from `fl_pi_estimator_new` which
+/// hasn't been freed, and not in use by another thread.
+#[no_mangle]
+pub unsafe extern "C" fn fl_pi_estimator_merge_estimate(estimator: *mut PiEstimator, other: Estimate) {
+    if let Some(estimator) = estimator.as_mut() {
+        estimator.merge_estimate(other);
+    }
+}
+
+/// The counts so far. An estimator which is null has no samples.
+///
+/// # Safety
+///
+/// `estimator` must be null or a pointer from `fl_pi_estimator_new` which
+/// hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn fl_pi_estimator_estimate(estimator: *const PiEstimator) -> Estimate {
+    estimator
+        .as_ref()
+        .map_or(Estimate::new(0, 0), |estimator| estimator.estimate())
+}
+
+/// The current estimate of pi, or NaN if there are no samples yet or
+/// `estimator` is null.
+///
+/// # Safety
+///
+/// `estimator` must be null or a pointer from `fl_pi_estimator_new` which
+/// hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn fl_pi_estimator_pi(estimator: *const PiEstimator) -> f64 {
+    fl_pi_estimator_estimate(estimator).pi()
+}
+
+/// Free an estimator. Does nothing if `estimator` is null.
+///
+/// # Safety
+///
+/// `estimator` must be null or a pointer from `fl_pi_estimator_new` which
+/// hasn't already been freed. It must not be used again afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn fl_pi_estimator_free(estimator: *mut PiEstimator) {
+    if !estimator.is_null() {
+        drop(Box::from_raw(estimator));
+    }
+}
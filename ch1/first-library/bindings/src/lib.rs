@@ -0,0 +1,16 @@
+// first-library built as a shared library (a cdylib), for calling from
+// other languages. The estimators themselves are all in first-library;
+// this crate only adds the interfaces to them.
+//
+// Build it with `cargo build --release -p first-library-bindings`, which
+// produces target/release/libfirst_library_bindings.so (.dylib on macOS,
+// .dll on Windows). For the web, build it with wasm-pack instead.
+
+// A C interface, which is also a set of plain exports of the WebAssembly
+// module for hosts that don't use the JavaScript bindings.
+mod ffi;
+
+// Bindings so the estimators can be called from JavaScript when the
+// library is compiled to WebAssembly.
+#[cfg(target_arch = "wasm32")]
+mod wasm;
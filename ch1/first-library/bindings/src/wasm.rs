@@ -0,0 +1,185 @@
+// JavaScript bindings for the estimators, for when the library is built
+// for the web (e.g. with `wasm-pack build --target web`).
+//
+// A long estimate run in one go would freeze the page until it finished,
+// because WebAssembly called from the main thread blocks it just like
+// JavaScript does. So the main entry point here is `PiEstimation`, which
+// does its work in chunks: JavaScript calls `runFor` with a small time
+// budget, lets the browser get on with drawing the page, then calls it
+// again until `done` is true.
+//
+// Progress reports go to a JavaScript callback rather than `println!`,
+// whose output goes nowhere in the browser.
+
+use js_sys::{Date, Function};
+use wasm_bindgen::prelude::*;
+
+use first_library::{worker_reply, Message, PiEstimator, Progress};
+
+/// Passes progress reports on to a JavaScript function, which is called
+/// with the sample count and the current estimate.
+struct JsProgress<'a>(&'a Function);
+
+impl Progress for JsProgress<'_> {
+    fn report(&mut self, samples: u64, pi: f64) {
+        // There's nothing useful to do here if the callback throws, and
+        // stopping the estimate would lose the work done so far, so the
+        // error is ignored.
+        let _ = self
+            .0
+            .call2(&JsValue::NULL, &JsValue::from(samples as f64), &JsValue::from(pi));
+    }
+}
+
+/// Estimate pi with `num_samples` samples, calling `progress(samples, pi)`
+/// every `display` samples. `display` must be at least 1.
+///
+/// This blocks until the estimate is finished, so it is best kept to short
+/// runs or used from a Web Worker. Use `PiEstimation` for long runs on
+/// the main thread.
+#[wasm_bindgen(js_name = estimatePi)]
+pub fn estimate_pi(num_samples: f64, display: f64, progress: &Function) -> f64 {
+    first_library::estimate_pi_with_progress(
+        num_samples as u64,
+        display as u64,
+        &mut rand::thread_rng(),
+        &mut JsProgress(progress),
+    )
+}
+
+/// Estimate pi with `num_samples` samples from a generator seeded with
+/// `seed`, without reporting progress.
+#[wasm_bindgen(js_name = estimatePiFast)]
+pub fn estimate_pi_fast(num_samples: f64, seed: u32) -> f64 {
+    first_library::estimate_pi_fast_seeded(num_samples as u64, seed as u64)
+}
+
+/// Estimate pi with `num_samples` samples using only integer arithmetic,
+/// so that the result is exactly the same as `estimate_pi_deterministic`
+/// gives natively for the same seed.
+#[wasm_bindgen(js_name = estimatePiDeterministic)]
+pub fn estimate_pi_deterministic(num_samples: f64, seed: u32) -> f64 {
+    first_library::estimate_pi_deterministic(num_samples as u64, seed as u64).pi()
+}
+
+/// Act as a worker in a distributed run: take one line of the protocol
+/// from the coordinator and return the line to reply with, or undefined if
+/// told to quit. A Web Worker can pass these back and forth with
+/// `postMessage`, and gets exactly the counts a native worker would.
+#[wasm_bindgen(js_name = handleWorkerMessage)]
+pub fn handle_worker_message(line: &str) -> Result<Option<String>, JsValue> {
+    match Message::parse(line).and_then(|message| worker_reply(&message)) {
+        Ok(reply) => Ok(reply.map(|reply| reply.to_string())),
+        Err(error) => Err(JsValue::from_str(&error.to_string())),
+    }
+}
+
+/// A long-running estimate of pi, carried out a chunk at a time so that
+/// the browser stays responsive.
+///
+/// Sample counts are passed as JavaScript numbers, which hold integers
+/// exactly up to 2^53.
+#[wasm_bindgen]
+pub struct PiEstimation {
+    estimator: PiEstimator,
+    num_samples: u64,
+    display: u64,
+    progress: Option<Function>,
+}
+
+#[wasm_bindgen]
+impl PiEstimation {
+    /// Prepare to estimate pi with `num_samples` samples, reporting
+    /// progress every `display` samples. Without a `seed` the generator
+    /// is seeded from the browser's source of randomness.
+    #[wasm_bindgen(constructor)]
+    pub fn new(num_samples: f64, display: f64, seed: Option<u32>) -> PiEstimation {
+        let estimator = match seed {
+            Some(seed) => PiEstimator::seeded(seed as u64),
+            None => PiEstimator::from_entropy(),
+        };
+
+        PiEstimation {
+            estimator,
+            num_samples: num_samples as u64,
+            display: (display as u64).max(1),
+            progress: None,
+        }
+    }
+
+    /// Set the function to call with `(samples, pi)` every `display`
+    /// samples.
+    #[wasm_bindgen(js_name = setProgress)]
+    pub fn set_progress(&mut self, callback: Function) {
+        self.progress = Some(callback);
+    }
+
+    /// Take up to `max_samples` more samples. Returns true once all of the
+    /// samples have been taken.
+    #[wasm_bindgen(js_name = runChunk)]
+    pub fn run_chunk(&mut self, max_samples: f64) -> bool {
+        let end = self.num_samples.min(self.estimator.samples() + max_samples as u64);
+        self.run_until(end);
+        self.done()
+    }
+
+    /// Keep taking samples for about `milliseconds`, then return so the
+    /// browser can update the page. Returns true once all of the samples
+    /// have been taken.
+    #[wasm_bindgen(js_name = runFor)]
+    pub fn run_for(&mut self, milliseconds: f64) -> bool {
+        // How many samples to take between looks at the clock. Small
+        // enough to stay close to the time budget, large enough that
+        // reading the clock costs next to nothing.
+        const CHECK_EVERY: u64 = 10_000;
+
+        let deadline = Date::now() + milliseconds;
+        while !self.done() && Date::now() < deadline {
+            let end = self.num_samples.min(self.estimator.samples() + CHECK_EVERY);
+            self.run_until(end);
+        }
+        self.done()
+    }
+
+    /// Whether all of the samples have been taken.
+    #[wasm_bindgen(getter)]
+    pub fn done(&self) -> bool {
+        self.estimator.samples() >= self.num_samples
+    }
+
+    /// The number of samples taken so far.
+    #[wasm_bindgen(getter)]
+    pub fn samples(&self) -> f64 {
+        self.estimator.samples() as f64
+    }
+
+    /// The current estimate for pi.
+    #[wasm_bindgen(getter)]
+    pub fn pi(&self) -> f64 {
+        self.estimator.pi()
+    }
+
+    /// The standard error of the current estimate.
+    #[wasm_bindgen(getter, js_name = standardError)]
+    pub fn standard_error(&self) -> f64 {
+        self.estimator.estimate().standard_error()
+    }
+}
+
+impl PiEstimation {
+    /// Take samples until `end` have been taken in total, stopping at each
+    /// multiple of `display` on the way to report progress.
+    fn run_until(&mut self, end: u64) {
+        while self.estimator.samples() < end {
+            let taken = self.estimator.samples();
+            let next_report = (taken / self.display + 1) * self.display;
+            self.estimator.add_samples(next_report.min(end) - taken);
+
+            if self.estimator.samples() == next_report {
+                if let Some(callback) = &self.progress {
+                    JsProgress(callback).report(next_report, self.estimator.pi());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Compiles the C example program against the bindings cdylib and runs
+//! it, to check that the C interface, the header and the example all still
+//! agree with each other.
+#![cfg(unix)]
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use first_library::{estimate_pi_fast_seeded, PiEstimator};
+
+#[test]
+fn c_example_compiles_and_runs() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // Cargo builds the cdylib alongside this test, in target/<profile>/deps
+    let test_exe = env::current_exe().unwrap();
+    let library_dir = test_exe.parent().unwrap();
+    let example = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("estimate_pi_c");
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(&compiler)
+        .arg(manifest_dir.join("examples/c/estimate_pi.c"))
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg("-L")
+        .arg(library_dir)
+        .arg("-lfirst_library_bindings")
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-o"])
+        .arg(&example)
+        .status()
+        .unwrap_or_else(|error| panic!("couldn't run the C compiler '{}': {}", compiler, error));
+    assert!(status.success(), "the C example didn't compile");
+
+    let output = Command::new(&example).output().unwrap();
+    assert!(output.status.success(), "the C example failed");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Everything the C program prints should match what we get from Rust
+    let mut merged = PiEstimator::seeded(1);
+    let mut second = PiEstimator::seeded(2);
+    merged.add_samples(500_000);
+    second.add_samples(500_000);
+    merged.merge(&second);
+
+    let expected = format!(
+        "fast: {:.6}\n\
+         deterministic: 785420 hits from 1000000 samples, pi = 3.141680\n\
+         merged: 1000000 samples, pi = {:.6} +/- {:.6}\n",
+        estimate_pi_fast_seeded(1_000_000, 1),
+        merged.pi(),
+        merged.estimate().standard_error()
+    );
+    assert_eq!(stdout, expected);
+}
+
+/// The C type for a Rust type used in the C interface.
+fn c_type(rust: &str) -> String {
+    match rust {
+        "u32" => "uint32_t".to_string(),
+        "u64" => "uint64_t".to_string(),
+        "f64" => "double".to_string(),
+        "Estimate" => "fl_estimate".to_string(),
+        "*mut PiEstimator" => "fl_pi_estimator *".to_string(),
+        "*const PiEstimator" => "const fl_pi_estimator *".to_string(),
+        _ => panic!("no C type for {}", rust),
+    }
+}
+
+/// Turn each function in src/ffi.rs into a C function pointer of the type
+/// its Rust signature calls for, initialised from the function the header
+/// declares. The C compiler then refuses anything missing from the header,
+/// or declared there with the wrong argument or return types.
+#[test]
+fn header_matches_the_rust_signatures() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let source = fs::read_to_string(manifest_dir.join("src/ffi.rs")).unwrap();
+
+    let mut check = String::from("#include \"first_library.h\"\n\n");
+    let mut count = 0;
+    for signature in source.lines().filter_map(|line| line.split("extern \"C\" fn ").nth(1)) {
+        // e.g. fl_estimate_pi_kernel(samples: u64, kernel: u32, seed: u64) -> Estimate {
+        let (name, rest) = signature.split_once('(').unwrap();
+        let (arguments, rest) = rest.split_once(')').unwrap();
+        let result = match rest.trim().trim_end_matches('{').trim() {
+            "" => "void".to_string(),
+            result => c_type(result.trim_start_matches("->").trim()),
+        };
+        let arguments: Vec<String> = arguments
+            .split(", ")
+            .map(|argument| c_type(argument.split_once(": ").unwrap().1))
+            .collect();
+        check += &format!(
+            "{} (*const check_{})({}) = {};\n",
+            result,
+            name,
+            arguments.join(", "),
+            name
+        );
+        count += 1;
+    }
+    assert!(count > 0);
+
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("check_signatures.c");
+    fs::write(&path, check).unwrap();
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = Command::new(&compiler)
+        .arg("-c")
+        .arg(&path)
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-o"])
+        .arg(path.with_extension("o"))
+        .status()
+        .unwrap_or_else(|error| panic!("couldn't run the C compiler '{}': {}", compiler, error));
+    assert!(status.success(), "first_library.h doesn't match src/ffi.rs");
+}
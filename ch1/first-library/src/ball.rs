@@ -0,0 +1,107 @@
+use std::f64::consts::PI;
+
+use rand::Rng;
+
+use crate::{volume, Volume};
+
+/// The result of estimate_ball_volume: a Monte Carlo estimate of the
+/// volume of the unit ball in some number of dimensions.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BallVolume {
+    /// The number of dimensions of the ball
+    pub dimensions: u32,
+    /// The estimate of the ball's volume, from points sampled in the cube
+    /// from -1 to 1 in every dimension
+    pub volume: Volume,
+}
+
+impl BallVolume {
+    /// The exact volume of the unit ball in this many dimensions.
+    pub fn exact(&self) -> f64 {
+        unit_ball_volume(self.dimensions)
+    }
+
+    /// How far the estimate is from the exact volume, as a fraction of the
+    /// exact volume.
+    pub fn relative_error(&self) -> f64 {
+        (self.volume.value() - self.exact()).abs() / self.exact()
+    }
+
+    /// The standard error of the estimate as a fraction of the estimate.
+    ///
+    /// This is the curse of dimensionality: the ball takes up a smaller
+    /// and smaller share of the cube as the dimensions go up, so fewer
+    /// and fewer of the points land inside it and this grows quickly.
+    pub fn relative_standard_error(&self) -> f64 {
+        self.volume.standard_error() / self.volume.value()
+    }
+
+    /// The estimate of pi found by solving the volume formula backwards.
+    ///
+    /// The volume of the d-ball is a rational number times pi^k, where k
+    /// is d / 2 rounded down. There is no pi in the formula for fewer than
+    /// 2 dimensions, so this returns NaN for them.
+    pub fn pi(&self) -> f64 {
+        let (coefficient, power) = coefficient_and_power(self.dimensions);
+        if power == 0 {
+            return f64::NAN;
+        }
+        (self.volume.value() / coefficient).powf(1.0 / power as f64)
+    }
+
+    /// The standard error of `pi`. A small change in the volume makes a
+    /// change in pi that is 1 / k times as large, relatively speaking.
+    pub fn pi_standard_error(&self) -> f64 {
+        let (_, power) = coefficient_and_power(self.dimensions);
+        self.pi() * self.relative_standard_error() / power as f64
+    }
+}
+
+/// The volume of the unit ball in `dimensions` dimensions.
+///
+/// The closed form is pi^(d/2) / Gamma(d/2 + 1). Rather than work out the
+/// gamma function we use the recurrence it leads to,
+/// V(d) = V(d - 2) * 2 pi / d, starting from V(0) = 1 and V(1) = 2.
+pub fn unit_ball_volume(dimensions: u32) -> f64 {
+    let (coefficient, power) = coefficient_and_power(dimensions);
+    coefficient * PI.powi(power)
+}
+
+/// Split the volume of the unit ball into its rational coefficient and its
+/// power of pi, e.g. 4/3 and 1 for 3 dimensions.
+fn coefficient_and_power(dimensions: u32) -> (f64, i32) {
+    let mut coefficient = if dimensions.is_multiple_of(2) { 1.0 } else { 2.0 };
+    let mut d = 2 + dimensions % 2;
+    while d <= dimensions {
+        coefficient *= 2.0 / d as f64;
+        d += 2;
+    }
+    (coefficient, (dimensions / 2) as i32)
+}
+
+/// Estimate the volume of the unit ball in `dimensions` dimensions by
+/// sampling points from the cube around it.
+///
+/// The quarter-circle test in estimate_pi_fast is the 2-dimensional case,
+/// using just one corner of the square. Comparing the estimates for
+/// increasing dimensions with the exact volumes shows how quickly plain
+/// Monte Carlo sampling runs out of steam.
+///
+/// # Arguments
+///
+/// * dimensions: the number of dimensions of the ball
+/// * num_samples: a 64-bit positive integer, the number of points to test
+/// * rng: the random number generator to take points from
+pub fn estimate_ball_volume<R: Rng + ?Sized>(dimensions: u32, num_samples: u64, rng: &mut R) -> BallVolume {
+    let bounds = vec![(-1.0, 1.0); dimensions as usize];
+    let volume = volume(
+        // The point is inside the ball if its distance from the centre is
+        // less than 1
+        |point| point.iter().map(|x| x * x).sum::<f64>() < 1.0,
+        &bounds,
+        num_samples,
+        rng,
+    );
+
+    BallVolume { dimensions, volume }
+}
@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+
+/// A minimal arbitrary-size unsigned integer, just big enough for the
+/// fixed-point arithmetic in the `series` module.
+///
+/// The number is stored as base 2^32 "limbs", least significant first,
+/// with no trailing zero limbs (so zero is an empty vector).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    /// Create a BigUint from a small number.
+    pub(crate) fn from_u32(value: u32) -> BigUint {
+        let mut result = BigUint { limbs: vec![value] };
+        result.normalize();
+        result
+    }
+
+    /// 10 to the power `exponent`.
+    pub(crate) fn pow10(exponent: usize) -> BigUint {
+        let mut result = BigUint::from_u32(1);
+        for _ in 0..exponent {
+            result.mul_small(10);
+        }
+        result
+    }
+
+    pub(crate) fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Multiply in place by a small number.
+    pub(crate) fn mul_small(&mut self, factor: u32) {
+        let mut carry: u64 = 0;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u64 * factor as u64 + carry;
+            *limb = product as u32;
+            carry = product >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+        self.normalize();
+    }
+
+    /// Divide in place by a small number, returning the remainder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub(crate) fn div_small(&mut self, divisor: u32) -> u32 {
+        assert!(divisor != 0, "division by zero");
+        let mut remainder: u64 = 0;
+        for limb in self.limbs.iter_mut().rev() {
+            let current = (remainder << 32) | *limb as u64;
+            *limb = (current / divisor as u64) as u32;
+            remainder = current % divisor as u64;
+        }
+        self.normalize();
+        remainder as u32
+    }
+
+    /// Add `other` to this number in place.
+    pub(crate) fn add(&mut self, other: &BigUint) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        let mut carry: u64 = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let sum = *limb as u64 + other.limbs.get(i).copied().unwrap_or(0) as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// Subtract `other` from this number in place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is bigger than this number.
+    pub(crate) fn sub(&mut self, other: &BigUint) {
+        assert!(*self >= *other, "subtraction would go below zero");
+        let mut borrow: i64 = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let mut difference = *limb as i64 - other.limbs.get(i).copied().unwrap_or(0) as i64 - borrow;
+            borrow = if difference < 0 { 1 } else { 0 };
+            if difference < 0 {
+                difference += 1 << 32;
+            }
+            *limb = difference as u32;
+        }
+        self.normalize();
+    }
+
+    /// Write the number out in decimal.
+    pub(crate) fn to_decimal(&self) -> String {
+        if self.is_zero() {
+            return "0".to_string();
+        }
+
+        // Peel off nine decimal digits at a time, least significant first
+        let mut value = self.clone();
+        let mut chunks = Vec::new();
+        while !value.is_zero() {
+            chunks.push(value.div_small(1_000_000_000));
+        }
+
+        // The most significant chunk has no leading zeros, every other
+        // chunk is padded out to nine digits
+        let mut result = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            result.push_str(&format!("{:09}", chunk));
+        }
+        result
+    }
+
+    /// Remove any zero limbs from the most significant end.
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        // With no trailing zero limbs, more limbs means a bigger number.
+        // Otherwise compare limb by limb from the most significant end.
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
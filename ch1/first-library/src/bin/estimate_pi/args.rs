@@ -0,0 +1,490 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use first_library::{geometric_estimators, Config, Kernel, TraceFormat};
+
+/// The help text printed for --help and after a usage error.
+pub const USAGE: &str = "\
+Usage: estimate_pi [OPTIONS]
+
+Estimate pi using Monte Carlo sampling.
+
+Options:
+  -n, --samples <N>     Number of samples to take [default: 10000000]
+  -d, --display <N>     Report progress every N samples [default: 5000]
+  -m, --method <NAME>   Which estimator to run: slow, fast, parallel, both
+                        (slow then fast) or geometric [default: both]
+  -e, --estimator <NAME>
+                        Experiment for the geometric method: dart, buffon,
+                        buffon-laplace, chord-endpoints, chord-radial, or
+                        all to compare them [default: all]
+  -s, --seed <SEED>     Seed for the random number generator, to make the
+                        run reproducible [default: random]
+  -t, --threads <N>     Worker threads for the parallel method
+                        [default: number of CPUs]
+  -k, --kernel <NAME>   Inner loop for the fast method: standard, pcg,
+                        batched or deterministic (the same result on every
+                        platform for a given seed) [default: standard]
+  -f, --format <NAME>   Output format: text or json [default: text]
+      --time-limit <SECONDS>
+                        Stop each run after this long, and print the
+                        estimate from the samples taken so far. Ctrl-C
+                        also stops a run early this way
+      --live            Show progress as a chart which is redrawn in place,
+                        instead of a line for every report (text only, and
+                        only for the slow method and checkpointed runs,
+                        which report their progress)
+      --trace <FILE>    Write a convergence trace to FILE: every progress
+                        report of the slow method, and the final result of
+                        the others, each labelled with its method
+      --trace-format <NAME>
+                        Trace format: csv or jsonl [default: csv]
+      --checkpoint <FILE>
+                        Save the run to FILE as it goes, so that it can be
+                        resumed if it is interrupted. Only for -m fast
+                        -k deterministic, whose result a resumed run
+                        matches exactly
+      --checkpoint-every <N>
+                        Samples between checkpoints [default: 100000000]
+      --resume <FILE>   Carry on the run saved in FILE, with the settings
+                        it was started with. Checkpoints keep being saved
+                        to FILE unless --checkpoint is also given. Also
+                        needs -m fast -k deterministic
+      --workers <N>     Spread the run across N worker processes, which
+                        take their samples with the deterministic kernel,
+                        so it needs -m fast -k deterministic. The result
+                        depends on the seed and --job-size, and a worker
+                        which dies has its job handed to another
+      --job-size <N>    Samples in each job given to a worker
+                        [default: 100000000]
+      --worker          Run as a worker: read jobs from stdin and write
+                        the results to stdout, ignoring the other options
+  -h, --help            Print this help and exit";
+
+/// Which estimator(s) to run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    Slow,
+    Fast,
+    Parallel,
+    Both,
+    Geometric,
+}
+
+/// How to print the results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Progress lines and "PI is: ..." for people to read
+    Text,
+    /// One JSON object per estimator, and no progress lines
+    Json,
+}
+
+/// The settings for a run of the program, taken from the command line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Args {
+    pub config: Config,
+    pub method: Method,
+    /// The geometric estimator to run, or None for all of them
+    pub estimator: Option<&'static str>,
+    pub format: Format,
+    pub live: bool,
+    pub trace: Option<PathBuf>,
+    pub trace_format: TraceFormat,
+    pub checkpoint: Option<PathBuf>,
+    pub checkpoint_every: u64,
+    pub resume: Option<PathBuf>,
+    /// The number of worker processes to spread the run across, if any
+    pub workers: Option<usize>,
+    pub job_size: u64,
+    /// Whether to act as a worker for another estimate_pi process
+    pub worker: bool,
+}
+
+/// Why the command line couldn't be turned into `Args`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgsError {
+    /// The user asked for the help text
+    Help,
+    /// The command line was wrong, with a message saying how
+    Invalid(String),
+}
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArgsError::Help => write!(f, "help requested"),
+            ArgsError::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Args {
+    /// Parse the command line arguments (not including the program name).
+    pub fn parse<I: IntoIterator<Item = String>>(arguments: I) -> Result<Args, ArgsError> {
+        let mut config = Config::builder().samples(10_000_000);
+        let mut method = Method::Both;
+        let mut estimator = None;
+        let mut format = Format::Text;
+        let mut live = false;
+        let mut trace = None;
+        let mut trace_format = TraceFormat::Csv;
+        let mut checkpoint = None;
+        let mut checkpoint_every = 100_000_000;
+        let mut resume = None;
+        let mut workers = None;
+        let mut job_size = 100_000_000;
+        let mut worker = false;
+
+        let mut arguments = arguments.into_iter();
+        while let Some(argument) = arguments.next() {
+            // Options can be given as "--samples 100" or "--samples=100"
+            let (name, inline_value) = match argument.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name.to_string(), Some(value.to_string())),
+                _ => (argument, None),
+            };
+
+            if name == "-h" || name == "--help" {
+                return Err(ArgsError::Help);
+            }
+
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| arguments.next())
+                    .ok_or_else(|| invalid(format!("{} needs a value", name)))
+            };
+
+            config = match name.as_str() {
+                "-n" | "--samples" => config.samples(parse_number(&name, &value()?)?),
+                "-d" | "--display" => config.display(parse_number(&name, &value()?)?),
+                "-s" | "--seed" => config.seed(parse_number(&name, &value()?)?),
+                "-t" | "--threads" => config.threads(parse_number(&name, &value()?)? as usize),
+                "-k" | "--kernel" => config.kernel(parse_kernel(&value()?)?),
+                "--time-limit" => config.time_budget(parse_seconds(&name, &value()?)?),
+                "-m" | "--method" => {
+                    method = parse_method(&value()?)?;
+                    config
+                }
+                "-e" | "--estimator" => {
+                    estimator = parse_estimator(&value()?)?;
+                    config
+                }
+                "-f" | "--format" => {
+                    format = parse_format(&value()?)?;
+                    config
+                }
+                "--live" => {
+                    live = true;
+                    config
+                }
+                "--trace" => {
+                    trace = Some(PathBuf::from(value()?));
+                    config
+                }
+                "--trace-format" => {
+                    trace_format = parse_trace_format(&value()?)?;
+                    config
+                }
+                "--checkpoint" => {
+                    checkpoint = Some(PathBuf::from(value()?));
+                    config
+                }
+                "--checkpoint-every" => {
+                    checkpoint_every = parse_number(&name, &value()?)?;
+                    config
+                }
+                "--resume" => {
+                    resume = Some(PathBuf::from(value()?));
+                    config
+                }
+                "--workers" => {
+                    workers = Some(parse_number(&name, &value()?)? as usize);
+                    config
+                }
+                "--job-size" => {
+                    job_size = parse_number(&name, &value()?)?;
+                    config
+                }
+                "--worker" => {
+                    worker = true;
+                    config
+                }
+                _ => return Err(invalid(format!("unexpected argument '{}'", name))),
+            };
+        }
+
+        // The library checks the settings make sense, e.g. that there's at
+        // least one sample to take
+        let config = config.build().map_err(|error| invalid(error.to_string()))?;
+        if live && format == Format::Json {
+            return Err(invalid("--live can't be used with --format json".to_string()));
+        }
+        if checkpoint_every == 0 {
+            return Err(invalid("--checkpoint-every must be at least 1".to_string()));
+        }
+        if workers == Some(0) {
+            return Err(invalid("--workers must be at least 1".to_string()));
+        }
+        if job_size == 0 {
+            return Err(invalid("--job-size must be at least 1".to_string()));
+        }
+        // Checkpoints carry on the deterministic kernel's samples, so any
+        // other method would quietly give a different result
+        let deterministic = method == Method::Fast && config.kernel() == Kernel::Deterministic;
+        if (checkpoint.is_some() || resume.is_some()) && !deterministic {
+            return Err(invalid("--checkpoint and --resume need -m fast -k deterministic".to_string()));
+        }
+        if workers.is_some() && (checkpoint.is_some() || resume.is_some()) {
+            return Err(invalid("--workers can't be used with --checkpoint or --resume".to_string()));
+        }
+        // The workers only know how to run the deterministic kernel
+        if workers.is_some() && !deterministic {
+            return Err(invalid("--workers needs -m fast -k deterministic".to_string()));
+        }
+        // The other runs only have a final result, so there'd be nothing
+        // for the chart to show
+        let reports_progress =
+            checkpoint.is_some() || resume.is_some() || matches!(method, Method::Slow | Method::Both);
+        if live && (workers.is_some() || !reports_progress) {
+            return Err(invalid(
+                "--live needs a run which reports its progress: -m slow, -m both, --checkpoint or --resume"
+                    .to_string(),
+            ));
+        }
+
+        Ok(Args {
+            config,
+            method,
+            estimator,
+            format,
+            live,
+            trace,
+            trace_format,
+            checkpoint,
+            checkpoint_every,
+            resume,
+            workers,
+            job_size,
+            worker,
+        })
+    }
+}
+
+fn invalid(message: String) -> ArgsError {
+    ArgsError::Invalid(message)
+}
+
+/// Parse a whole number, allowing underscores as separators like Rust
+/// does (e.g. 10_000_000).
+fn parse_number(name: &str, value: &str) -> Result<u64, ArgsError> {
+    value
+        .replace('_', "")
+        .parse()
+        .map_err(|_| invalid(format!("{} expects a whole number, got '{}'", name, value)))
+}
+
+/// Parse a positive number of seconds, which can have a fractional part.
+fn parse_seconds(name: &str, value: &str) -> Result<Duration, ArgsError> {
+    match value.parse::<f64>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(Duration::from_secs_f64(seconds)),
+        _ => Err(invalid(format!("{} expects a positive number of seconds, got '{}'", name, value))),
+    }
+}
+
+fn parse_method(value: &str) -> Result<Method, ArgsError> {
+    match value {
+        "slow" => Ok(Method::Slow),
+        "fast" => Ok(Method::Fast),
+        "parallel" => Ok(Method::Parallel),
+        "both" => Ok(Method::Both),
+        "geometric" => Ok(Method::Geometric),
+        _ => Err(invalid(format!(
+            "unknown method '{}', expected slow, fast, parallel, both or geometric",
+            value
+        ))),
+    }
+}
+
+fn parse_kernel(value: &str) -> Result<Kernel, ArgsError> {
+    Kernel::ALL
+        .iter()
+        .copied()
+        .find(|kernel| kernel.name() == value)
+        .ok_or_else(|| {
+            invalid(format!(
+                "unknown kernel '{}', expected standard, pcg, batched or deterministic",
+                value
+            ))
+        })
+}
+
+/// Find the geometric estimator called `value`, or None for "all".
+fn parse_estimator(value: &str) -> Result<Option<&'static str>, ArgsError> {
+    if value == "all" {
+        return Ok(None);
+    }
+    let estimators = geometric_estimators();
+    let names: Vec<&'static str> = estimators.iter().map(|estimator| estimator.name()).collect();
+    match names.iter().find(|&&name| name == value) {
+        Some(&name) => Ok(Some(name)),
+        None => Err(invalid(format!(
+            "unknown estimator '{}', expected {} or all",
+            value,
+            names.join(", ")
+        ))),
+    }
+}
+
+fn parse_format(value: &str) -> Result<Format, ArgsError> {
+    match value {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(invalid(format!("unknown format '{}', expected text or json", value))),
+    }
+}
+
+fn parse_trace_format(value: &str) -> Result<TraceFormat, ArgsError> {
+    match value {
+        "csv" => Ok(TraceFormat::Csv),
+        "jsonl" => Ok(TraceFormat::JsonLines),
+        _ => Err(invalid(format!("unknown trace format '{}', expected csv or jsonl", value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(arguments: &[&str]) -> Result<Args, ArgsError> {
+        Args::parse(arguments.iter().map(|argument| argument.to_string()))
+    }
+
+    /// The message from a command line which should be rejected.
+    fn error(arguments: &[&str]) -> String {
+        match parse(arguments) {
+            Err(ArgsError::Invalid(message)) => message,
+            other => panic!("{:?} should be invalid, got {:?}", arguments, other),
+        }
+    }
+
+    #[test]
+    fn defaults() {
+        let args = parse(&[]).unwrap();
+        assert_eq!(args.config.samples(), 10_000_000);
+        assert_eq!(args.config.display(), 5000);
+        assert_eq!(args.config.seed(), None);
+        assert_eq!(args.config.kernel(), Kernel::Standard);
+        assert_eq!(args.method, Method::Both);
+        assert_eq!(args.estimator, None);
+        assert_eq!(args.format, Format::Text);
+        assert_eq!(args.trace_format, TraceFormat::Csv);
+        assert!(!args.live && !args.worker);
+        assert_eq!(args.workers, None);
+    }
+
+    #[test]
+    fn values_can_follow_an_equals_sign_and_have_underscores() {
+        let args = parse(&["--samples=1_000", "-d", "100", "--seed=7", "-m", "fast", "--kernel=batched"]);
+        let args = args.unwrap();
+        assert_eq!(args.config.samples(), 1000);
+        assert_eq!(args.config.display(), 100);
+        assert_eq!(args.config.seed(), Some(7));
+        assert_eq!(args.config.kernel(), Kernel::Batched);
+        assert_eq!(args.method, Method::Fast);
+    }
+
+    #[test]
+    fn other_options() {
+        let args = parse(&[
+            "-m",
+            "geometric",
+            "-e",
+            "buffon",
+            "-f",
+            "json",
+            "--trace",
+            "out.jsonl",
+            "--trace-format",
+            "jsonl",
+            "--time-limit",
+            "1.5",
+        ])
+        .unwrap();
+        assert_eq!(args.method, Method::Geometric);
+        assert_eq!(args.estimator, Some("buffon"));
+        assert_eq!(args.format, Format::Json);
+        assert_eq!(args.trace, Some(PathBuf::from("out.jsonl")));
+        assert_eq!(args.trace_format, TraceFormat::JsonLines);
+        assert_eq!(args.config.time_budget(), Some(Duration::from_millis(1500)));
+
+        let args = parse(&["-m", "fast", "-k", "deterministic", "--workers", "4", "--job-size", "1000"]).unwrap();
+        assert_eq!(args.workers, Some(4));
+        assert_eq!(args.job_size, 1000);
+
+        let args = parse(&["-m", "fast", "-k", "deterministic", "--checkpoint", "run.ckpt", "--live"]).unwrap();
+        assert_eq!(args.checkpoint, Some(PathBuf::from("run.ckpt")));
+        assert!(args.live);
+    }
+
+    #[test]
+    fn help() {
+        assert_eq!(parse(&["-h"]), Err(ArgsError::Help));
+        assert_eq!(parse(&["-n", "10", "--help"]), Err(ArgsError::Help));
+    }
+
+    #[test]
+    fn bad_values() {
+        assert_eq!(error(&["--frobnicate"]), "unexpected argument '--frobnicate'");
+        assert_eq!(error(&["-n"]), "-n needs a value");
+        assert_eq!(error(&["-n", "lots"]), "-n expects a whole number, got 'lots'");
+        assert_eq!(
+            error(&["--time-limit", "-1"]),
+            "--time-limit expects a positive number of seconds, got '-1'"
+        );
+        assert!(error(&["-m", "quick"]).starts_with("unknown method 'quick'"));
+        assert!(error(&["-k", "turbo"]).starts_with("unknown kernel 'turbo'"));
+        assert!(error(&["-e", "coin"]).starts_with("unknown estimator 'coin'"));
+        assert!(error(&["-f", "xml"]).starts_with("unknown format 'xml'"));
+        assert!(error(&["--trace-format", "xml"]).starts_with("unknown trace format 'xml'"));
+    }
+
+    #[test]
+    fn settings_the_library_rejects() {
+        assert_eq!(error(&["-n", "0"]), first_library::Error::ZeroSamples.to_string());
+        assert_eq!(error(&["-d", "0"]), first_library::Error::ZeroDisplay.to_string());
+    }
+
+    #[test]
+    fn zero_counts() {
+        let deterministic = |option: &str| error(&["-m", "fast", "-k", "deterministic", option, "0"]);
+        assert_eq!(deterministic("--checkpoint-every"), "--checkpoint-every must be at least 1");
+        assert_eq!(deterministic("--workers"), "--workers must be at least 1");
+        assert_eq!(deterministic("--job-size"), "--job-size must be at least 1");
+    }
+
+    #[test]
+    fn conflicting_options() {
+        assert_eq!(error(&["--live", "-f", "json"]), "--live can't be used with --format json");
+
+        let checkpoint = "--checkpoint and --resume need -m fast -k deterministic";
+        assert_eq!(error(&["--checkpoint", "run.ckpt"]), checkpoint);
+        assert_eq!(error(&["-m", "fast", "--resume", "run.ckpt"]), checkpoint);
+        assert_eq!(error(&["-k", "deterministic", "--checkpoint", "run.ckpt"]), checkpoint);
+
+        let workers = "--workers needs -m fast -k deterministic";
+        assert_eq!(error(&["--workers", "2"]), workers);
+        assert_eq!(error(&["-m", "parallel", "-k", "deterministic", "--workers", "2"]), workers);
+        assert_eq!(
+            error(&["-m", "fast", "-k", "deterministic", "--workers", "2", "--checkpoint", "run.ckpt"]),
+            "--workers can't be used with --checkpoint or --resume"
+        );
+
+        let live = "--live needs a run which reports its progress: -m slow, -m both, --checkpoint or --resume";
+        for method in ["fast", "parallel", "geometric"] {
+            assert_eq!(error(&["--live", "-m", method]), live);
+        }
+        assert_eq!(error(&["--live", "-m", "fast", "-k", "deterministic", "--workers", "2"]), live);
+    }
+}
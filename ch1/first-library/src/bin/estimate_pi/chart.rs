@@ -0,0 +1,191 @@
+use std::f64::consts::PI;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use first_library::Progress;
+
+/// The size of the chart in characters. Each braille character is a grid
+/// of 2 by 4 dots, so the chart is 120 by 48 dots.
+const WIDTH: usize = 60;
+const HEIGHT: usize = 12;
+
+/// How often the chart is redrawn. Progress reports can arrive thousands
+/// of times a second, far faster than anyone can read.
+const REDRAW_EVERY: Duration = Duration::from_millis(100);
+
+/// Shows the progress of a run as a chart of the estimate converging on
+/// pi, redrawn in place, along with the current error, the speed and how
+/// long is left.
+pub struct LiveChart {
+    total: u64,
+    /// The number of samples already taken when we started, e.g. by the
+    /// run a checkpoint was saved from, so they don't count towards speed
+    first: u64,
+    start: Instant,
+    last_draw: Option<Instant>,
+    /// The lines drawn last time, which need to be drawn over
+    lines_drawn: usize,
+    /// The latest estimate in each column of dots, where the columns
+    /// divide the run's samples up equally
+    columns: Vec<Option<f64>>,
+    latest: (u64, f64),
+}
+
+impl LiveChart {
+    /// Prepare a chart for a run of `total` samples, of which `first` have
+    /// been taken already.
+    pub fn new(total: u64, first: u64) -> LiveChart {
+        LiveChart {
+            total,
+            first,
+            start: Instant::now(),
+            last_draw: None,
+            lines_drawn: 0,
+            columns: vec![None; 2 * WIDTH],
+            latest: (first, f64::NAN),
+        }
+    }
+
+    /// Draw the chart one last time, so that it shows the final report.
+    pub fn finish(mut self) {
+        self.draw();
+    }
+
+    fn draw(&mut self) {
+        let mut screen = String::new();
+
+        // Move back up to the top of the last chart and clear it away
+        if self.lines_drawn > 0 {
+            screen.push_str(&format!("\x1b[{}A\x1b[J", self.lines_drawn));
+        }
+
+        // Zoom in to fit the later half of the estimates, keeping pi in the
+        // middle. The early ones are far out and would squash the rest of
+        // the line flat, so they are allowed to go off the chart.
+        let estimates: Vec<f64> = self.columns.iter().flatten().copied().collect();
+        let furthest = estimates[estimates.len() / 2..]
+            .iter()
+            .map(|pi| (pi - PI).abs())
+            .fold(1e-4, f64::max);
+
+        let rows = self.plot(furthest);
+        for (row, line) in rows.iter().enumerate() {
+            let label = if row == 0 {
+                format!("{:.6}", PI + furthest)
+            } else if row == HEIGHT / 2 {
+                format!("{:.6}", PI)
+            } else if row == HEIGHT - 1 {
+                format!("{:.6}", PI - furthest)
+            } else {
+                String::new()
+            };
+            screen.push_str(&format!("{:>9} ┤{}\n", label, line));
+        }
+
+        let (samples, pi) = self.latest;
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let rate = (samples - self.first) as f64 / elapsed;
+        let eta = match (self.total - samples) as f64 / rate {
+            seconds if seconds.is_finite() => format_seconds(seconds),
+            _ => "-".to_string(),
+        };
+        screen.push_str(&format!(
+            "estimate {:.8}  error {:.2e}\nsamples {}/{} ({:.1}%)  {:.0} samples/s  ETA {}\n",
+            pi,
+            (pi - PI).abs(),
+            samples,
+            self.total,
+            100.0 * samples as f64 / self.total as f64,
+            rate,
+            eta
+        ));
+
+        self.lines_drawn = HEIGHT + 2;
+        self.last_draw = Some(Instant::now());
+
+        // A failure to write to the terminal isn't worth stopping the run
+        // for, so errors are ignored
+        let mut stdout = io::stdout().lock();
+        let _ = stdout.write_all(screen.as_bytes());
+        let _ = stdout.flush();
+    }
+
+    /// Turn the estimates into rows of braille characters, with a dotted
+    /// line across at pi. The estimates run from pi - furthest at the
+    /// bottom to pi + furthest at the top.
+    fn plot(&self, furthest: f64) -> Vec<String> {
+        let dot_rows = 4 * HEIGHT;
+        let mut dots = vec![vec![false; 2 * WIDTH]; dot_rows];
+
+        let middle = dot_rows / 2;
+        for column in (0..2 * WIDTH).step_by(4) {
+            dots[middle][column] = true;
+        }
+
+        let to_row = |pi: f64| {
+            let fraction = (0.5 - 0.5 * (pi - PI) / furthest).clamp(0.0, 1.0);
+            (fraction * (dot_rows - 1) as f64).round() as usize
+        };
+
+        // Join each column's dot to the one before, so the line doesn't
+        // break up where the estimate moves quickly
+        let mut previous = None;
+        for (column, pi) in self.columns.iter().enumerate() {
+            if let Some(pi) = *pi {
+                let row = to_row(pi);
+                let from = previous.unwrap_or(row);
+                for dot in dots.iter_mut().take(row.max(from) + 1).skip(row.min(from)) {
+                    dot[column] = true;
+                }
+                previous = Some(row);
+            }
+        }
+
+        (0..HEIGHT)
+            .map(|row| (0..WIDTH).map(|column| braille(&dots, row, column)).collect())
+            .collect()
+    }
+}
+
+impl Progress for LiveChart {
+    fn report(&mut self, samples: u64, pi: f64) {
+        let column = (samples.saturating_sub(1) as f64 / self.total as f64 * (2 * WIDTH) as f64) as usize;
+        self.columns[column.min(2 * WIDTH - 1)] = Some(pi);
+        self.latest = (samples, pi);
+
+        let due = self.last_draw.is_none_or(|last| last.elapsed() >= REDRAW_EVERY);
+        if due {
+            self.draw();
+        }
+    }
+}
+
+/// The braille character showing the 2 by 4 block of dots for one place
+/// in the chart.
+fn braille(dots: &[Vec<bool>], row: usize, column: usize) -> char {
+    // The bit for each dot, from the Unicode braille patterns block
+    const BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+    let mut pattern = 0;
+    for (dy, bits) in BITS.iter().enumerate() {
+        for (dx, bit) in bits.iter().enumerate() {
+            if dots[4 * row + dy][2 * column + dx] {
+                pattern |= bit;
+            }
+        }
+    }
+    std::char::from_u32(0x2800 + pattern).unwrap_or(' ')
+}
+
+/// A number of seconds as e.g. "1h 02m 03s".
+fn format_seconds(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
@@ -0,0 +1,37 @@
+use std::sync::OnceLock;
+
+use first_library::CancelToken;
+
+/// The token Ctrl-C cancels. The signal handler can't be given anything,
+/// so it has to find the token here.
+static INTERRUPTED: OnceLock<CancelToken> = OnceLock::new();
+
+/// Make Ctrl-C cancel the returned token instead of ending the program, so
+/// that a run can stop and still print the estimate it has so far. A
+/// second Ctrl-C ends the program straight away, in case the first one
+/// isn't noticed.
+///
+/// Only Unix-like systems are supported. Elsewhere Ctrl-C ends the program
+/// as usual, and the token is never cancelled.
+pub fn cancel_on_interrupt() -> CancelToken {
+    let token = INTERRUPTED.get_or_init(CancelToken::new).clone();
+
+    #[cfg(unix)]
+    unsafe {
+        libc::signal(libc::SIGINT, on_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t);
+    }
+
+    token
+}
+
+/// Called by the operating system when Ctrl-C is pressed. Only a few
+/// things are safe to do in a signal handler; setting an atomic flag and
+/// `_exit` are among them.
+#[cfg(unix)]
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    match INTERRUPTED.get() {
+        Some(token) if !token.is_cancelled() => token.cancel(),
+        // 130 is what shells report for a program ended by Ctrl-C
+        _ => unsafe { libc::_exit(130) },
+    }
+}
@@ -0,0 +1,367 @@
+//! This file will become our runnable program.
+//! As it's the only program in our library's crate, we can run it simply
+//! with `cargo run`. If there were multiple programs we would need to add
+//! the --bin argument to cargo run, e.g. `cargo run --bin estimate_pi`.
+//!
+//! Options go after a `--`, so that cargo knows they're for our program,
+//! e.g. `cargo run -- --samples 1000 --method fast`. Run with `--help` to
+//! see them all.
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, IsTerminal};
+use std::path::Path;
+use std::process::{self, Command, Stdio};
+use std::thread;
+
+use first_library::{
+    estimate_pi_checkpointed_cancellable, estimate_pi_geometric_cancellable, geometric_estimators,
+    json_number, run_worker, CancelToken, Checkpoint, Coordinator, Estimate, PrintProgress, Progress,
+    TraceFormat, TraceWriter,
+};
+
+// Reading the command line is in its own module to keep this file short.
+mod args;
+use args::{Args, ArgsError, Format, Method};
+
+// The chart shown with --live.
+mod chart;
+use chart::LiveChart;
+
+// Stopping a run with Ctrl-C.
+mod interrupt;
+use interrupt::cancel_on_interrupt;
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(ArgsError::Help) => {
+            println!("{}", args::USAGE);
+            return;
+        }
+        Err(error) => {
+            // Exit code 2 is the usual one for a mistake on the command line
+            eprintln!("error: {}\n\n{}", error, args::USAGE);
+            process::exit(2);
+        }
+    };
+
+    // A worker takes its orders from the coordinator, not the command line
+    if args.worker {
+        if let Err(error) = run_worker(io::stdin().lock(), io::stdout().lock()) {
+            eprintln!("error: worker stopped: {}", error);
+            process::exit(1);
+        }
+        return;
+    }
+
+    // Open the trace file before starting, so that we find out about a
+    // bad path straight away rather than after a long run
+    let mut trace = args
+        .trace
+        .as_ref()
+        .map(|path| open_trace(path, args.trace_format));
+
+    // A resumed run carries on saving to the file it came from, unless
+    // it's been told to save somewhere else
+    let checkpoint = args.checkpoint.as_ref().or(args.resume.as_ref());
+
+    // From here on, Ctrl-C stops the run and prints what it has so far
+    let cancel = cancel_on_interrupt();
+
+    match (checkpoint, args.method) {
+        (Some(path), _) => run_checkpointed(&args, path, trace.as_mut(), &cancel),
+        (None, _) if args.workers.is_some() => run_distributed(&args, trace.as_mut(), &cancel),
+        (None, Method::Slow) => run_slow(&args, trace.as_mut(), &cancel),
+        (None, Method::Fast) => run_fast(&args, trace.as_mut(), &cancel),
+        (None, Method::Parallel) => run_parallel(&args, trace.as_mut(), &cancel),
+        (None, Method::Both) => {
+            run_slow(&args, trace.as_mut(), &cancel);
+            // Ctrl-C stops the whole program, not just the slow run
+            if !cancel.is_cancelled() {
+                run_fast(&args, trace.as_mut(), &cancel);
+            }
+        }
+        (None, Method::Geometric) => run_geometric(&args, trace.as_mut(), &cancel),
+    }
+
+    if let Some(trace) = trace {
+        if let Err(error) = trace.finish() {
+            eprintln!("error: couldn't write the trace file: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// A convergence trace being written to a file.
+type Trace = TraceWriter<BufWriter<File>>;
+
+fn open_trace(path: &Path, format: TraceFormat) -> Trace {
+    let result = File::create(path).and_then(|file| TraceWriter::new(BufWriter::new(file), format));
+    result.unwrap_or_else(|error| {
+        eprintln!("error: couldn't create {}: {}", path.display(), error);
+        process::exit(1);
+    })
+}
+
+/// Everywhere the progress reports of a run go: the screen, as lines or as
+/// a live chart, and the trace file.
+struct Reports<'a> {
+    lines: bool,
+    chart: Option<LiveChart>,
+    trace: Option<&'a mut Trace>,
+}
+
+impl<'a> Reports<'a> {
+    /// Prepare to report on a run of `total` samples, of which `first`
+    /// have been taken already.
+    fn new(args: &Args, total: u64, first: u64, trace: Option<&'a mut Trace>) -> Reports<'a> {
+        // Progress on the screen would get in the way of the JSON output
+        let text = args.format == Format::Text;
+        // Redrawing the chart in place only works on a terminal, so when
+        // the output is going to a file or a pipe it gets plain lines
+        let live = args.live && io::stdout().is_terminal();
+        Reports {
+            lines: text && !live,
+            chart: if text && live { Some(LiveChart::new(total, first)) } else { None },
+            trace,
+        }
+    }
+
+    /// Bring the screen up to date once the run has finished.
+    fn finish(self) {
+        if let Some(chart) = self.chart {
+            chart.finish();
+        }
+    }
+}
+
+impl Progress for Reports<'_> {
+    fn report(&mut self, samples: u64, pi: f64) {
+        if self.lines {
+            PrintProgress.report(samples, pi);
+        }
+        if let Some(chart) = self.chart.as_mut() {
+            chart.report(samples, pi);
+        }
+        if let Some(trace) = self.trace.as_mut() {
+            trace.report(samples, pi);
+        }
+    }
+}
+
+/// Run our estimate_pi function and print the result.
+fn run_slow(args: &Args, trace: Option<&mut Trace>, cancel: &CancelToken) {
+    let trace = start_run(trace, "slow");
+    let mut reports = Reports::new(args, args.config.samples(), 0, trace);
+    let estimate = args.config.estimate_pi_cancellable(&mut reports, cancel);
+    reports.finish();
+    note_if_stopped(estimate.samples, args.config.samples());
+    print_result(args, "slow", "PI is", estimate);
+}
+
+/// Run the "fast" version of estimate_pi. You'll see it completes in
+/// noticeably less time than the slow version.
+fn run_fast(args: &Args, trace: Option<&mut Trace>, cancel: &CancelToken) {
+    let trace = start_run(trace, "fast");
+    let estimate = args.config.estimate_pi_fast_cancellable(cancel);
+    trace_result(trace, estimate);
+    note_if_stopped(estimate.samples, args.config.samples());
+    print_result(args, "fast", "PI (fast) is", estimate);
+}
+
+/// Run the multi-threaded version of estimate_pi_fast.
+fn run_parallel(args: &Args, trace: Option<&mut Trace>, cancel: &CancelToken) {
+    let trace = start_run(trace, "parallel");
+    let estimate = args.config.estimate_pi_parallel_cancellable(cancel);
+    trace_result(trace, estimate);
+    note_if_stopped(estimate.samples, args.config.samples());
+    print_result(args, "parallel", "PI (parallel) is", estimate);
+}
+
+/// Say so if a run was stopped by Ctrl-C or --time-limit before taking all
+/// its samples, as its estimate is less accurate than was asked for. This
+/// goes to stderr so that it doesn't get mixed up with the results.
+fn note_if_stopped(taken: u64, total: u64) {
+    if taken < total {
+        eprintln!("stopped early after {} of {} samples", taken, total);
+    }
+}
+
+/// Run one or all of the geometric estimators, e.g. Buffon's needle, with
+/// the same number of trials each so that their accuracy can be compared.
+fn run_geometric(args: &Args, mut trace: Option<&mut Trace>, cancel: &CancelToken) {
+    let chosen = geometric_estimators()
+        .into_iter()
+        .filter(|estimator| args.estimator.is_none_or(|name| estimator.name() == name));
+
+    for estimator in chosen {
+        // Each estimator gets the whole time budget, but Ctrl-C stops them
+        // all
+        if cancel.is_cancelled() {
+            break;
+        }
+        if let Some(trace) = trace.as_mut() {
+            trace.start_run("geometric", Some(estimator.name()));
+        }
+        let result = estimate_pi_geometric_cancellable(
+            &*estimator,
+            args.config.samples(),
+            &mut *args.config.rng(),
+            &cancel.with_optional_budget(args.config.time_budget()),
+        );
+        if let Some(trace) = trace.as_mut() {
+            trace.report(result.trials, result.pi);
+        }
+        note_if_stopped(result.trials, args.config.samples());
+
+        match args.format {
+            // The spread is the error per trial, so lower is better
+            Format::Text => println!(
+                "PI ({}) is: {} ± {:.2e} (spread per trial {:.3})",
+                estimator.name(),
+                result.pi,
+                result.standard_error,
+                result.spread()
+            ),
+            Format::Json => println!(
+                "{{\"method\":\"geometric\",\"estimator\":\"{}\",\"samples\":{},\"pi\":{},\"standard_error\":{}}}",
+                estimator.name(),
+                result.trials,
+                json_number(result.pi),
+                json_number(result.standard_error)
+            ),
+        }
+    }
+}
+
+/// Run the deterministic estimator, saving a checkpoint to `path` every
+/// so often so that the run can be resumed if it's interrupted.
+fn run_checkpointed(args: &Args, path: &Path, trace: Option<&mut Trace>, cancel: &CancelToken) {
+    let checkpoint = match &args.resume {
+        Some(resume) => Checkpoint::load(resume).unwrap_or_else(|error| {
+            eprintln!("error: couldn't resume from {}: {}", resume.display(), error);
+            process::exit(1);
+        }),
+        // A run can only be resumed if we know its seed, so if we weren't
+        // given one we pick one now and it is saved with the checkpoints
+        None => Checkpoint::new(
+            args.config.samples(),
+            args.config.seed().unwrap_or_else(rand::random),
+        ),
+    };
+
+    let (total, first) = (checkpoint.samples(), checkpoint.estimate().samples);
+    let trace = start_run(trace, "checkpointed");
+    let mut reports = Reports::new(args, total, first, trace);
+    let cancel = cancel.with_optional_budget(args.config.time_budget());
+    let every = args.checkpoint_every;
+    let result = estimate_pi_checkpointed_cancellable(checkpoint, path, every, &mut reports, &cancel);
+    reports.finish();
+
+    match result {
+        Ok(estimate) => {
+            note_if_stopped(estimate.samples, total);
+            if estimate.samples < total {
+                eprintln!("carry on with --resume {}", path.display());
+            }
+            print_result(args, "checkpointed", "PI (checkpointed) is", estimate);
+        }
+        Err(error) => {
+            eprintln!("error: couldn't save the checkpoint {}: {}", path.display(), error);
+            process::exit(1);
+        }
+    }
+}
+
+/// Spread the run across worker processes, each a copy of this program
+/// started with --worker, and add up their results.
+fn run_distributed(args: &Args, trace: Option<&mut Trace>, cancel: &CancelToken) {
+    let trace = start_run(trace, "distributed");
+    let program = env::current_exe().unwrap_or_else(|error| {
+        eprintln!("error: couldn't find this program to start workers: {}", error);
+        process::exit(1);
+    });
+    let seed = args.config.seed().unwrap_or_else(rand::random);
+    let coordinator = Coordinator::new(args.config.samples(), args.job_size, seed);
+
+    // With --time-limit, no more jobs are handed out once time is up, and
+    // the ones already running are allowed to finish
+    let cancel = cancel.with_optional_budget(args.config.time_budget());
+
+    // Each worker is looked after by its own thread, which passes it jobs
+    // until there are none left
+    thread::scope(|scope| {
+        for number in 1..=args.workers.unwrap_or(1) {
+            let (program, coordinator, cancel) = (&program, &coordinator, &cancel);
+            scope.spawn(move || {
+                let started = Command::new(program)
+                    .arg("--worker")
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .spawn();
+                let mut child = match started {
+                    Ok(child) => child,
+                    Err(error) => {
+                        eprintln!("warning: couldn't start worker {}: {}", number, error);
+                        return;
+                    }
+                };
+
+                let reader = BufReader::new(child.stdout.take().expect("worker stdout is piped"));
+                let writer = child.stdin.take().expect("worker stdin is piped");
+                if let Err(error) = coordinator.serve(reader, writer, cancel) {
+                    // Ctrl-C reaches the workers too, so they are expected
+                    // to stop then
+                    if !cancel.is_cancelled() {
+                        eprintln!("warning: worker {} failed, its job will be run again: {}", number, error);
+                    }
+                    let _ = child.kill();
+                }
+                let _ = child.wait();
+            });
+        }
+    });
+
+    let estimate = coordinator.estimate();
+    trace_result(trace, estimate);
+    note_if_stopped(estimate.samples, args.config.samples());
+    print_result(args, "distributed", "PI (distributed) is", estimate);
+
+    // Ctrl-C is a fine reason to stop, but running out of workers isn't
+    if !coordinator.is_done() && !cancel.is_cancelled() {
+        eprintln!("error: every worker failed before the run was finished");
+        process::exit(1);
+    }
+}
+
+/// Label the trace records which follow as coming from a run of `method`,
+/// timed from now.
+fn start_run<'a>(mut trace: Option<&'a mut Trace>, method: &'static str) -> Option<&'a mut Trace> {
+    if let Some(trace) = trace.as_mut() {
+        trace.start_run(method, None);
+    }
+    trace
+}
+
+/// The fast estimators don't report progress, so the trace just gets their
+/// final result.
+fn trace_result(trace: Option<&mut Trace>, estimate: Estimate) {
+    if let Some(trace) = trace {
+        trace.report(estimate.samples, estimate.pi());
+    }
+}
+
+fn print_result(args: &Args, method: &str, label: &str, estimate: Estimate) {
+    match args.format {
+        Format::Text => println!("{}: {}", label, estimate.pi()),
+        Format::Json => println!(
+            "{{\"method\":\"{}\",\"samples\":{},\"hits\":{},\"pi\":{},\"standard_error\":{}}}",
+            method,
+            estimate.samples,
+            estimate.hits,
+            json_number(estimate.pi()),
+            json_number(estimate.standard_error())
+        ),
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::Estimate;
+
+/// A handle for stopping a running estimate early.
+///
+/// Pass one to any of the `_cancellable` estimators, and call `cancel` on
+/// a clone of it from anywhere else - another thread, a signal handler or
+/// a GUI's stop button. The estimator checks it every
+/// `CancelToken::CHECK_EVERY` samples and stops soon after, returning the
+/// samples it had taken so far.
+///
+/// A token can also cancel itself after a time budget, see `with_budget`.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancelToken {
+    /// How many samples the cancellable estimators take between checks of
+    /// their token: 65536. Checking is cheap, but not as cheap as a sample,
+    /// and this many samples only take a fraction of a millisecond. It is a
+    /// multiple of `LANES`, so that the batched kernel always uses whole
+    /// batches.
+    pub const CHECK_EVERY: u64 = 1 << 16;
+
+    /// Create a token which hasn't been cancelled.
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Make a token which is cancelled along with this one, and also by
+    /// itself once `budget` has passed from now.
+    pub fn with_budget(&self, budget: Duration) -> CancelToken {
+        let deadline = Instant::now() + budget;
+        CancelToken {
+            cancelled: Arc::clone(&self.cancelled),
+            deadline: Some(self.deadline.map_or(deadline, |earlier| earlier.min(deadline))),
+        }
+    }
+
+    /// Like `with_budget`, but a `budget` of None just gives a clone of
+    /// this token. Handy for settings where the time limit is optional.
+    pub fn with_optional_budget(&self, budget: Option<Duration>) -> CancelToken {
+        match budget {
+            Some(budget) => self.with_budget(budget),
+            None => self.clone(),
+        }
+    }
+
+    /// Ask every estimate using this token, or a clone of it, to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token has been cancelled, or its time budget has run
+    /// out.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Take up to N samples, `CancelToken::CHECK_EVERY` at a time, stopping early if `cancel`
+/// is cancelled. `count_hits` takes the given number of samples and
+/// returns how many landed inside the quarter-circle.
+#[allow(non_snake_case)]
+pub(crate) fn count_hits_until<F>(N: u64, cancel: &CancelToken, mut count_hits: F) -> Estimate
+where
+    F: FnMut(u64) -> u64,
+{
+    let mut estimate = Estimate::new(0, 0);
+    while estimate.samples < N && !cancel.is_cancelled() {
+        let chunk = CancelToken::CHECK_EVERY.min(N - estimate.samples);
+        estimate += Estimate::new(count_hits(chunk), chunk);
+    }
+    estimate
+}
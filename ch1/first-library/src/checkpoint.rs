@@ -0,0 +1,257 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::deterministic::count_hits_integer;
+use crate::{CancelToken, Estimate, Pcg32, Progress};
+
+/// The state of a long estimate of pi which can be saved to a file and
+/// picked up again later, so that an interrupted run doesn't lose the
+/// samples it has already taken.
+///
+/// A checkpointed run takes its samples exactly as
+/// `estimate_pi_deterministic` does, so however many times it is stopped
+/// and resumed, it finishes with the same result as an uninterrupted run
+/// with the same seed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    seed: u64,
+    samples: u64,
+    estimate: Estimate,
+    rng: Pcg32,
+}
+
+/// The first line of every checkpoint file, so that we can tell if we've
+/// been given something else.
+const HEADER: &str = "# first-library checkpoint v1";
+
+impl Checkpoint {
+    /// Start a run of `samples` samples from a generator seeded with `seed`.
+    pub fn new(samples: u64, seed: u64) -> Checkpoint {
+        Checkpoint {
+            seed,
+            samples,
+            estimate: Estimate::new(0, 0),
+            rng: Pcg32::seeded(seed),
+        }
+    }
+
+    /// The seed the run started from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The total number of samples the run will take.
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// The counts so far.
+    pub fn estimate(&self) -> Estimate {
+        self.estimate
+    }
+
+    /// Whether all of the samples have been taken.
+    pub fn is_done(&self) -> bool {
+        self.estimate.samples >= self.samples
+    }
+
+    /// Take up to `n` more samples, stopping early if the run is finished.
+    pub fn run(&mut self, n: u64) {
+        let n = n.min(self.samples - self.estimate.samples);
+        let hits = count_hits_integer(n, &mut self.rng);
+        self.estimate += Estimate::new(hits, n);
+    }
+
+    /// Write the checkpoint in its text format: one `name=value` line for
+    /// each setting, count and piece of generator state.
+    pub fn write_to<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let (state, increment) = self.rng.to_parts();
+        writeln!(out, "{}", HEADER)?;
+        writeln!(out, "seed={}", self.seed)?;
+        writeln!(out, "samples={}", self.samples)?;
+        writeln!(out, "done={}", self.estimate.samples)?;
+        writeln!(out, "hits={}", self.estimate.hits)?;
+        writeln!(out, "rng_state={}", state)?;
+        writeln!(out, "rng_increment={}", increment)
+    }
+
+    /// Read a checkpoint written by `write_to`.
+    ///
+    /// # Errors
+    ///
+    /// As well as errors from reading, returns an error of kind
+    /// `InvalidData` if the input isn't a valid checkpoint.
+    pub fn read_from<R: BufRead>(input: R) -> io::Result<Checkpoint> {
+        let mut lines = input.lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(invalid("not a checkpoint file".to_string()));
+        }
+
+        let mut seed = None;
+        let mut samples = None;
+        let mut done = None;
+        let mut hits = None;
+        let mut state = None;
+        let mut increment = None;
+
+        for line in lines {
+            let line = line?;
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("expected name=value, got '{}'", line)))?;
+            let value: u64 = value.parse().map_err(|_| {
+                invalid(format!("{} should be a whole number, got '{}'", name, value))
+            })?;
+            let field = match name {
+                "seed" => &mut seed,
+                "samples" => &mut samples,
+                "done" => &mut done,
+                "hits" => &mut hits,
+                "rng_state" => &mut state,
+                "rng_increment" => &mut increment,
+                _ => return Err(invalid(format!("unknown setting '{}'", name))),
+            };
+            *field = Some(value);
+        }
+
+        let require = |value: Option<u64>, name: &str| {
+            value.ok_or_else(|| invalid(format!("{} is missing", name)))
+        };
+        let (seed, samples) = (require(seed, "seed")?, require(samples, "samples")?);
+        let (done, hits) = (require(done, "done")?, require(hits, "hits")?);
+        let (state, increment) = (require(state, "rng_state")?, require(increment, "rng_increment")?);
+
+        // Catch files which have been edited or mixed up, rather than
+        // carrying on to a result which would look fine but be wrong
+        if hits > done || done > samples {
+            return Err(invalid("the counts don't add up".to_string()));
+        }
+        if increment.is_multiple_of(2) {
+            return Err(invalid("rng_increment must be odd".to_string()));
+        }
+
+        Ok(Checkpoint {
+            seed,
+            samples,
+            estimate: Estimate::new(hits, done),
+            rng: Pcg32::from_parts(state, increment),
+        })
+    }
+
+    /// Save the checkpoint to the file at `path`.
+    ///
+    /// The checkpoint is written to a temporary file first, which then
+    /// replaces `path`. So if the program is stopped part way through
+    /// saving, the previous checkpoint is still there.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+
+        let mut out = BufWriter::new(File::create(&temporary)?);
+        self.write_to(&mut out)?;
+        out.into_inner()?.sync_all()?;
+        fs::rename(&temporary, path)
+    }
+
+    /// Load a checkpoint saved by `save`.
+    pub fn load(path: &Path) -> io::Result<Checkpoint> {
+        Checkpoint::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Carry a run on from `checkpoint` until it is finished, saving it to
+/// `path` every `every` samples and when it finishes.
+///
+/// Start with `Checkpoint::new` for a fresh run, or `Checkpoint::load` to
+/// resume one.
+///
+/// # Arguments
+///
+/// * checkpoint: where the run has got to
+/// * path: the file to save checkpoints to
+/// * every: a 64-bit positive integer, the number of samples between
+///   checkpoints
+/// * progress: receives the sample count and current estimate at each
+///   checkpoint
+///
+/// # Errors
+///
+/// Returns the error if a checkpoint couldn't be saved. The samples taken
+/// since the last successful save are lost.
+///
+/// # Panics
+///
+/// Panics if `every` is zero.
+pub fn estimate_pi_checkpointed<P: Progress + ?Sized>(
+    checkpoint: Checkpoint,
+    path: &Path,
+    every: u64,
+    progress: &mut P,
+) -> io::Result<Estimate> {
+    estimate_pi_checkpointed_cancellable(checkpoint, path, every, progress, &CancelToken::new())
+}
+
+/// Version of estimate_pi_checkpointed which stops early if `cancel` is
+/// cancelled, checking it every `CancelToken::CHECK_EVERY` samples. A
+/// checkpoint is saved before stopping, so the run can be resumed from
+/// there, and the estimate so far is returned.
+///
+/// # Arguments
+///
+/// * checkpoint: where the run has got to
+/// * path: the file to save checkpoints to
+/// * every: a 64-bit positive integer, the number of samples between
+///   checkpoints
+/// * progress: receives the sample count and current estimate at each
+///   checkpoint
+/// * cancel: says when to stop early
+///
+/// # Errors
+///
+/// Returns the error if a checkpoint couldn't be saved. The samples taken
+/// since the last successful save are lost.
+///
+/// # Panics
+///
+/// Panics if `every` is zero.
+pub fn estimate_pi_checkpointed_cancellable<P: Progress + ?Sized>(
+    mut checkpoint: Checkpoint,
+    path: &Path,
+    every: u64,
+    progress: &mut P,
+    cancel: &CancelToken,
+) -> io::Result<Estimate> {
+    assert!(every > 0, "the checkpoint interval must be at least 1");
+
+    let mut unsaved = 0;
+    while !checkpoint.is_done() && !cancel.is_cancelled() {
+        let chunk = CancelToken::CHECK_EVERY.min(every - unsaved);
+        checkpoint.run(chunk);
+        unsaved += chunk;
+
+        if unsaved == every || checkpoint.is_done() {
+            save_and_report(&checkpoint, path, progress)?;
+            unsaved = 0;
+        }
+    }
+
+    // Keep the samples taken since the last checkpoint, if we were stopped
+    // part way to the next one
+    if unsaved > 0 {
+        save_and_report(&checkpoint, path, progress)?;
+    }
+
+    Ok(checkpoint.estimate())
+}
+
+fn save_and_report<P: Progress + ?Sized>(checkpoint: &Checkpoint, path: &Path, progress: &mut P) -> io::Result<()> {
+    checkpoint.save(path)?;
+    let estimate = checkpoint.estimate();
+    progress.report(estimate.samples, estimate.pi());
+    Ok(())
+}
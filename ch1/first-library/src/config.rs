@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use rand::RngCore;
+
+use crate::{CancelToken, Error, Estimate, Kernel, Progress};
+
+/// Checked settings for a run of the estimators.
+///
+/// A Config can only be made through `Config::builder()`, which rejects
+/// settings the estimators can't work with, such as zero samples. So once
+/// we have a Config, running it can't go wrong.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    samples: u64,
+    display: u64,
+    seed: Option<u64>,
+    threads: usize,
+    kernel: Kernel,
+    time_budget: Option<Duration>,
+}
+
+impl Config {
+    /// Start building a Config. Everything but the number of samples has a
+    /// default.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    /// The number of samples to take.
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// How often estimate_pi reports its progress.
+    pub fn display(&self) -> u64 {
+        self.display
+    }
+
+    /// The seed for the random number generator, if the run should be
+    /// reproducible.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// The number of threads estimate_pi_parallel uses.
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// The inner loop estimate_pi_fast uses.
+    pub fn kernel(&self) -> Kernel {
+        self.kernel
+    }
+
+    /// How long a run may take before it stops with the samples it has
+    /// taken so far, if there is a limit.
+    pub fn time_budget(&self) -> Option<Duration> {
+        self.time_budget
+    }
+
+    /// The random number generator to use: a seeded one if there is a seed,
+    /// otherwise the thread-local one.
+    pub fn rng(&self) -> Box<dyn RngCore> {
+        match self.seed {
+            Some(seed) => Box::new(crate::seeded_rng(seed)),
+            None => Box::new(rand::thread_rng()),
+        }
+    }
+
+    /// Run estimate_pi with these settings, reporting progress to
+    /// `progress`.
+    pub fn estimate_pi<P: Progress + ?Sized>(&self, progress: &mut P) -> Estimate {
+        self.estimate_pi_cancellable(progress, &CancelToken::new())
+    }
+
+    /// Run estimate_pi with these settings, stopping early if `cancel` is
+    /// cancelled or the time budget runs out.
+    pub fn estimate_pi_cancellable<P: Progress + ?Sized>(&self, progress: &mut P, cancel: &CancelToken) -> Estimate {
+        let cancel = cancel.with_optional_budget(self.time_budget);
+        crate::estimate_pi_cancellable(self.samples, self.display, &mut *self.rng(), progress, &cancel)
+    }
+
+    /// Run estimate_pi_fast with these settings. Apart from the standard
+    /// kernel, which can use the thread-local generator, the kernels need
+    /// a seed, so one is picked at random if there isn't one.
+    pub fn estimate_pi_fast(&self) -> Estimate {
+        self.estimate_pi_fast_cancellable(&CancelToken::new())
+    }
+
+    /// Run estimate_pi_fast with these settings, stopping early if
+    /// `cancel` is cancelled or the time budget runs out.
+    pub fn estimate_pi_fast_cancellable(&self, cancel: &CancelToken) -> Estimate {
+        let cancel = cancel.with_optional_budget(self.time_budget);
+        match (self.kernel, self.seed) {
+            (Kernel::Standard, None) => {
+                crate::estimate_pi_fast_cancellable(self.samples, &mut *self.rng(), &cancel)
+            }
+            (kernel, seed) => {
+                let seed = seed.unwrap_or_else(rand::random);
+                crate::estimate_pi_fast_kernel_cancellable(self.samples, kernel, seed, &cancel)
+            }
+        }
+    }
+
+    /// Run estimate_pi_parallel with these settings. Without a seed, one is
+    /// picked at random.
+    pub fn estimate_pi_parallel(&self) -> Estimate {
+        self.estimate_pi_parallel_cancellable(&CancelToken::new())
+    }
+
+    /// Run estimate_pi_parallel with these settings, stopping early if
+    /// `cancel` is cancelled or the time budget runs out.
+    pub fn estimate_pi_parallel_cancellable(&self, cancel: &CancelToken) -> Estimate {
+        let cancel = cancel.with_optional_budget(self.time_budget);
+        let seed = self.seed.unwrap_or_else(rand::random);
+        crate::estimate_pi_parallel_cancellable(self.samples, self.threads, seed, &cancel)
+    }
+}
+
+/// Builds a `Config`, checking the settings in `build`.
+#[derive(Clone, Debug)]
+pub struct ConfigBuilder {
+    samples: u64,
+    display: u64,
+    seed: Option<u64>,
+    threads: usize,
+    kernel: Kernel,
+    time_budget: Option<Duration>,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> ConfigBuilder {
+        ConfigBuilder {
+            samples: 0,
+            display: 5000,
+            seed: None,
+            threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            kernel: Kernel::Standard,
+            time_budget: None,
+        }
+    }
+}
+
+impl ConfigBuilder {
+    /// Set the number of samples to take. This must be at least 1.
+    pub fn samples(mut self, samples: u64) -> ConfigBuilder {
+        self.samples = samples;
+        self
+    }
+
+    /// Set how often estimate_pi reports its progress. This must be at
+    /// least 1, and defaults to every 5000 samples.
+    pub fn display(mut self, display: u64) -> ConfigBuilder {
+        self.display = display;
+        self
+    }
+
+    /// Seed the random number generator, to make the run reproducible.
+    pub fn seed(mut self, seed: u64) -> ConfigBuilder {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set the number of threads estimate_pi_parallel uses. This must be at
+    /// least 1, and defaults to the number of CPUs.
+    pub fn threads(mut self, threads: usize) -> ConfigBuilder {
+        self.threads = threads;
+        self
+    }
+
+    /// Set the inner loop estimate_pi_fast uses. Defaults to
+    /// `Kernel::Standard`.
+    pub fn kernel(mut self, kernel: Kernel) -> ConfigBuilder {
+        self.kernel = kernel;
+        self
+    }
+
+    /// Limit how long each run may take. When the time is up the run stops
+    /// and returns an estimate from the samples it has taken. By default
+    /// there is no limit.
+    pub fn time_budget(mut self, budget: Duration) -> ConfigBuilder {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    /// Check the settings and make the Config.
+    pub fn build(self) -> Result<Config, Error> {
+        check_samples(self.samples)?;
+        check_display(self.display)?;
+        check_threads(self.threads)?;
+
+        Ok(Config {
+            samples: self.samples,
+            display: self.display,
+            seed: self.seed,
+            threads: self.threads,
+            kernel: self.kernel,
+            time_budget: self.time_budget,
+        })
+    }
+}
+
+pub(crate) fn check_samples(samples: u64) -> Result<(), Error> {
+    if samples == 0 {
+        return Err(Error::ZeroSamples);
+    }
+    Ok(())
+}
+
+fn check_display(display: u64) -> Result<(), Error> {
+    if display == 0 {
+        return Err(Error::ZeroDisplay);
+    }
+    Ok(())
+}
+
+fn check_threads(threads: usize) -> Result<(), Error> {
+    if threads == 0 {
+        return Err(Error::ZeroThreads);
+    }
+    Ok(())
+}
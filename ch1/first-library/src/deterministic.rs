@@ -0,0 +1,46 @@
+use rand::RngCore;
+
+use crate::{Estimate, Pcg32};
+
+/// Estimate pi from N samples so that the same N and `seed` give exactly
+/// the same hit count on every platform, whether that is x86_64, ARM or a
+/// WebAssembly runtime.
+///
+/// The other seeded estimators are reproducible on one machine, but turn
+/// random bits into floating point numbers and then square and add them,
+/// and the last bit of that can depend on the platform. Here everything
+/// is done in whole numbers: the random numbers come from our own `Pcg32`,
+/// each point is a pair of 32-bit integers (x, y) standing for
+/// (x / 2^32, y / 2^32), and the check x^2 + y^2 < 1 becomes
+/// x^2 + y^2 < 2^64, which is worked out exactly.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+/// * seed: a 64-bit integer used to initialise the random number generator
+#[allow(non_snake_case)]
+pub fn estimate_pi_deterministic(N: u64, seed: u64) -> Estimate {
+    Estimate::new(count_hits_integer(N, &mut Pcg32::seeded(seed)), N)
+}
+
+/// Take N points made of 32-bit integers from `rng` and return M, the
+/// number which landed inside the quarter-circle.
+#[allow(non_snake_case)]
+pub(crate) fn count_hits_integer<R: RngCore + ?Sized>(N: u64, rng: &mut R) -> u64 {
+    // The radius of the circle, 1, is 2^32 in these units, so its square
+    // is 2^64. That is one too big for a u64, so the sums are done in u128.
+    const RADIUS_SQUARED: u128 = 1 << 64;
+
+    let mut M: u64 = 0;
+
+    for _ in 0..N {
+        let x = rng.next_u32() as u128;
+        let y = rng.next_u32() as u128;
+
+        if x*x + y*y < RADIUS_SQUARED {
+            M += 1;
+        }
+    }
+
+    M
+}
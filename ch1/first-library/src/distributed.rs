@@ -0,0 +1,281 @@
+// Spreading one big estimate across several worker processes, or anything
+// else which can pass lines of text back and forth: pipes to a child
+// process, a local socket, or messages to a WebAssembly worker.
+//
+// The coordinator splits the run into jobs and hands them out one at a
+// time. Each message is one line:
+//
+//   RUN <job> <seed> <samples>   coordinator to worker: take `samples`
+//                                samples from a generator seeded with `seed`
+//   HITS <job> <hits> <samples>  worker to coordinator: the job's counts
+//   QUIT                         coordinator to worker: there's no more work
+//
+// Workers run estimate_pi_deterministic, so a job gives the same counts
+// whichever worker runs it, on whatever platform. If a worker dies, its
+// job is handed to another one, and the run still finishes with exactly
+// the result it would have had otherwise.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use rand::RngCore;
+
+use crate::{estimate_pi_deterministic, CancelToken, Estimate, Pcg32};
+
+/// A share of a distributed run, for one worker to carry out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Job {
+    /// The job's number, which the worker's reply must quote
+    pub id: u64,
+    /// The seed for estimate_pi_deterministic
+    pub seed: u64,
+    /// The number of samples to take
+    pub samples: u64,
+}
+
+/// One line of the protocol between a coordinator and its workers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Message {
+    /// Coordinator to worker: carry out a job
+    Run(Job),
+    /// Worker to coordinator: the counts from the job numbered `job`
+    Hits { job: u64, estimate: Estimate },
+    /// Coordinator to worker: there is no more work, so stop
+    Quit,
+}
+
+impl Message {
+    /// Read a message from one line of text, as written by `Display`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the line isn't a valid
+    /// message.
+    pub fn parse(line: &str) -> io::Result<Message> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |word: &str| {
+            word.parse::<u64>()
+                .map_err(|_| invalid(format!("expected a whole number, got '{}'", word)))
+        };
+
+        match words.as_slice() {
+            ["RUN", id, seed, samples] => Ok(Message::Run(Job {
+                id: number(id)?,
+                seed: number(seed)?,
+                samples: number(samples)?,
+            })),
+            ["HITS", job, hits, samples] => {
+                let (hits, samples) = (number(hits)?, number(samples)?);
+                if hits > samples {
+                    return Err(invalid("a job can't have more hits than samples".to_string()));
+                }
+                Ok(Message::Hits {
+                    job: number(job)?,
+                    estimate: Estimate::new(hits, samples),
+                })
+            }
+            ["QUIT"] => Ok(Message::Quit),
+            _ => Err(invalid(format!("unknown message '{}'", line))),
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Message::Run(job) => write!(f, "RUN {} {} {}", job.id, job.seed, job.samples),
+            Message::Hits { job, estimate } => write!(f, "HITS {} {} {}", job, estimate.hits, estimate.samples),
+            Message::Quit => write!(f, "QUIT"),
+        }
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// A worker's reply to a message from the coordinator: the counts for a
+/// job, or None if it has been told to quit.
+///
+/// # Errors
+///
+/// Returns an error of kind `InvalidData` if the message is one only a
+/// worker should send.
+pub fn worker_reply(message: &Message) -> io::Result<Option<Message>> {
+    match *message {
+        Message::Run(job) => Ok(Some(Message::Hits {
+            job: job.id,
+            estimate: estimate_pi_deterministic(job.samples, job.seed),
+        })),
+        Message::Quit => Ok(None),
+        Message::Hits { .. } => Err(invalid("workers don't take HITS messages".to_string())),
+    }
+}
+
+/// Act as a worker: carry out the jobs read from `input`, writing each
+/// reply to `output`, until told to quit or `input` ends.
+pub fn run_worker<R: BufRead, W: Write>(input: R, mut output: W) -> io::Result<()> {
+    for line in input.lines() {
+        match worker_reply(&Message::parse(&line?)?)? {
+            Some(reply) => {
+                writeln!(output, "{}", reply)?;
+                // The coordinator is waiting for this, so it mustn't sit
+                // in a buffer
+                output.flush()?;
+            }
+            None => break,
+        }
+    }
+    Ok(())
+}
+
+/// Hands out the jobs of a distributed run and adds up the results.
+///
+/// Each worker is driven by its own call to `serve`, typically each on its
+/// own thread, and they all share one Coordinator. Jobs given to a worker
+/// which fails are put back to be handed to another.
+#[derive(Debug)]
+pub struct Coordinator {
+    state: Mutex<State>,
+    /// Signalled whenever a job finishes or is put back
+    changed: Condvar,
+}
+
+#[derive(Debug)]
+struct State {
+    waiting: VecDeque<Job>,
+    running: usize,
+    estimate: Estimate,
+}
+
+impl Coordinator {
+    /// Split a run of `samples` samples into jobs of `job_size` samples
+    /// (the last may be smaller), each with its own seed generated from
+    /// `seed`. The result depends only on these three numbers, not on the
+    /// number of workers or which of them run which job.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `job_size` is zero.
+    pub fn new(samples: u64, job_size: u64, seed: u64) -> Coordinator {
+        assert!(job_size > 0, "jobs need at least one sample each");
+
+        let mut seeds = Pcg32::seeded(seed);
+        let waiting = (0..samples.div_ceil(job_size))
+            .map(|id| Job {
+                id,
+                seed: seeds.next_u64(),
+                samples: job_size.min(samples - id * job_size),
+            })
+            .collect();
+
+        Coordinator {
+            state: Mutex::new(State {
+                waiting,
+                running: 0,
+                estimate: Estimate::new(0, 0),
+            }),
+            changed: Condvar::new(),
+        }
+    }
+
+    /// The counts from the jobs finished so far.
+    pub fn estimate(&self) -> Estimate {
+        self.lock().estimate
+    }
+
+    /// Whether every job has finished.
+    pub fn is_done(&self) -> bool {
+        let state = self.lock();
+        state.waiting.is_empty() && state.running == 0
+    }
+
+    /// Give jobs to a worker, which reads them from `writer` and replies
+    /// on `reader`, until there are none left or `cancel` is cancelled.
+    /// The worker is then told to quit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the worker can't be written to, stops replying
+    /// or replies with nonsense. Its job is put back for another worker,
+    /// and this worker shouldn't be used again.
+    pub fn serve<R: BufRead, W: Write>(&self, mut reader: R, mut writer: W, cancel: &CancelToken) -> io::Result<()> {
+        while let Some(job) = self.next_job(cancel) {
+            match run_job(job, &mut reader, &mut writer) {
+                Ok(estimate) => self.finish(estimate),
+                Err(error) => {
+                    self.put_back(job);
+                    return Err(error);
+                }
+            }
+        }
+
+        writeln!(writer, "{}", Message::Quit)?;
+        writer.flush()
+    }
+
+    /// Take the next job waiting to be done. If none are waiting, but some
+    /// are still running, wait to see whether any of them are put back.
+    /// Returns None once every job has finished or `cancel` is cancelled.
+    fn next_job(&self, cancel: &CancelToken) -> Option<Job> {
+        // The token can't wake us, so look at it every so often
+        const CHECK_EVERY: Duration = Duration::from_millis(100);
+
+        let mut state = self.lock();
+        loop {
+            if cancel.is_cancelled() {
+                return None;
+            }
+            if let Some(job) = state.waiting.pop_front() {
+                state.running += 1;
+                return Some(job);
+            }
+            if state.running == 0 {
+                return None;
+            }
+            state = match self.changed.wait_timeout(state, CHECK_EVERY) {
+                Ok((state, _)) => state,
+                Err(poisoned) => poisoned.into_inner().0,
+            };
+        }
+    }
+
+    fn finish(&self, estimate: Estimate) {
+        let mut state = self.lock();
+        state.running -= 1;
+        state.estimate += estimate;
+        self.changed.notify_all();
+    }
+
+    fn put_back(&self, job: Job) {
+        let mut state = self.lock();
+        state.running -= 1;
+        state.waiting.push_back(job);
+        self.changed.notify_all();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // The state is never left half-updated, so it's still good if a
+        // thread panicked while holding the lock
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Send one job to a worker and wait for its counts.
+fn run_job<R: BufRead, W: Write>(job: Job, reader: &mut R, writer: &mut W) -> io::Result<Estimate> {
+    writeln!(writer, "{}", Message::Run(job))?;
+    writer.flush()?;
+
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "the worker stopped replying"));
+    }
+
+    match Message::parse(&line)? {
+        Message::Hits { job: id, estimate } if id == job.id && estimate.samples == job.samples => Ok(estimate),
+        reply => Err(invalid(format!("expected the counts for job {}, got '{}'", job.id, reply))),
+    }
+}
@@ -0,0 +1,39 @@
+use core::fmt;
+
+/// The ways the input to an estimator can be invalid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The number of samples was zero, so there is nothing to estimate from
+    ZeroSamples,
+    /// The display interval was zero, which would mean reporting progress
+    /// infinitely often
+    ZeroDisplay,
+    /// The number of threads was zero, so nothing would take the samples
+    ZeroThreads,
+    /// The batch size of an adaptive estimate was zero, so it would never
+    /// make progress
+    ZeroBatchSize,
+    /// The tolerance of an adaptive estimate wasn't a positive number, so
+    /// it could never be met
+    InvalidTolerance,
+    /// There were too few samples to measure the variance of an estimate
+    /// from, e.g. a single point
+    TooFewSamples,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            Error::ZeroSamples => "the number of samples must be at least 1",
+            Error::ZeroDisplay => "the display interval must be at least 1",
+            Error::ZeroThreads => "the number of threads must be at least 1",
+            Error::ZeroBatchSize => "the batch size must be at least 1",
+            Error::InvalidTolerance => "the tolerance must be a positive number",
+            Error::TooFewSamples => "there are too few samples to measure the variance",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,162 @@
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::estimate_pi_fast::count_hits;
+use crate::config::check_samples;
+use crate::{math, Error, Estimate};
+
+/// Settings for estimate_pi_adaptive. Sampling stops as soon as any one of
+/// the stopping conditions is met.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveOptions {
+    /// Stop once the standard error of the estimate is below this
+    pub tolerance: f64,
+    /// Stop once this many samples have been taken
+    pub max_samples: u64,
+    /// Stop once this much wall-clock time has passed, if set
+    pub time_budget: Option<Duration>,
+    /// How many samples to take between checks of the stopping conditions
+    pub batch_size: u64,
+}
+
+impl Default for AdaptiveOptions {
+    fn default() -> AdaptiveOptions {
+        AdaptiveOptions {
+            tolerance: 1e-4,
+            max_samples: 1_000_000_000,
+            time_budget: None,
+            batch_size: 100_000,
+        }
+    }
+}
+
+impl AdaptiveOptions {
+    /// Check that the options can be used for an adaptive estimate.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::ZeroSamples` if `max_samples` is zero,
+    /// `Error::ZeroBatchSize` if `batch_size` is zero, or
+    /// `Error::InvalidTolerance` if `tolerance` isn't a positive number.
+    pub fn validate(&self) -> Result<(), Error> {
+        check_samples(self.max_samples)?;
+        if self.batch_size == 0 {
+            return Err(Error::ZeroBatchSize);
+        }
+        if self.tolerance.is_nan() || self.tolerance <= 0.0 {
+            return Err(Error::InvalidTolerance);
+        }
+        Ok(())
+    }
+}
+
+/// Which of the stopping conditions ended an adaptive run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// The standard error dropped below the requested tolerance
+    Tolerance,
+    /// The maximum number of samples was reached
+    MaxSamples,
+    /// The time budget ran out
+    Deadline,
+}
+
+/// The result of estimate_pi_adaptive: the estimate itself, plus the reason
+/// sampling stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AdaptiveEstimate {
+    pub estimate: Estimate,
+    pub stopped_by: StopReason,
+}
+
+/// Estimate pi without choosing the number of samples up front.
+///
+/// Samples are taken in batches of `options.batch_size`. After each batch
+/// we stop if the standard error has dropped below `options.tolerance`,
+/// if `options.max_samples` have been taken, or if `options.time_budget`
+/// has run out - whichever happens first.
+///
+/// The standard error compared with the tolerance is worked out as if
+/// there had been two more hits and two more misses, so that a first few
+/// samples which all hit or all miss can't pass for a perfect estimate.
+///
+/// # Arguments
+///
+/// * options: the stopping conditions and batch size
+/// * rng: the random number generator to take samples from
+///
+/// # Panics
+///
+/// Panics if `options.batch_size` or `options.max_samples` is zero.
+/// `try_estimate_pi_adaptive` returns an error instead.
+#[allow(non_snake_case)]
+pub fn estimate_pi_adaptive<R: Rng + ?Sized>(
+    options: &AdaptiveOptions,
+    rng: &mut R,
+) -> AdaptiveEstimate {
+    assert!(options.batch_size > 0, "batch_size must be positive");
+    assert!(options.max_samples > 0, "max_samples must be positive");
+
+    // Work out when we have to stop by, if there's a time budget at all
+    let deadline = options.time_budget.map(|budget| Instant::now() + budget);
+
+    let mut M: u64 = 0;
+    let mut N: u64 = 0;
+
+    loop {
+        // Never take more than max_samples in total, even in the last batch
+        let batch = options.batch_size.min(options.max_samples - N);
+        M += count_hits(batch, rng);
+        N += batch;
+
+        let estimate = Estimate::new(M, N);
+
+        // Check the stopping conditions, most desirable first
+        let stopped_by = if cautious_standard_error(estimate) < options.tolerance {
+            StopReason::Tolerance
+        } else if N >= options.max_samples {
+            StopReason::MaxSamples
+        } else if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            StopReason::Deadline
+        } else {
+            continue;
+        };
+
+        return AdaptiveEstimate { estimate, stopped_by };
+    }
+}
+
+/// The standard error of an estimate of pi, worked out as if there had
+/// been two more hits and two more misses (the Agresti-Coull adjustment).
+///
+/// The usual standard error comes from the fraction of hits, p, and is 0
+/// when p is 0 or 1. After a handful of samples that's quite likely, and
+/// would look like a perfect estimate of 4 or 0. The adjusted fraction
+/// is never 0 or 1, so the error stays large until there are enough
+/// samples to trust, and it makes no real difference after that.
+fn cautious_standard_error(estimate: Estimate) -> f64 {
+    let n = estimate.samples as f64 + 4.0;
+    let p = (estimate.hits as f64 + 2.0) / n;
+    4.0 * math::sqrt(p * (1.0 - p) / n)
+}
+
+/// Version of estimate_pi_adaptive which checks its options first,
+/// returning an error instead of panicking.
+///
+/// # Arguments
+///
+/// * options: the stopping conditions and batch size
+/// * rng: the random number generator to take samples from
+///
+/// # Errors
+///
+/// Returns the error from `AdaptiveOptions::validate` if the options are
+/// invalid.
+pub fn try_estimate_pi_adaptive<R: Rng + ?Sized>(
+    options: &AdaptiveOptions,
+    rng: &mut R,
+) -> Result<AdaptiveEstimate, Error> {
+    options.validate()?;
+    Ok(estimate_pi_adaptive(options, rng))
+}
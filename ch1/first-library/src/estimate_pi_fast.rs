@@ -1,7 +1,10 @@
-#[allow(non_snake_case)]
 // Use the rand Rng trait (needed for the .gen() function to be available)
 use rand::Rng;
 
+use crate::{Estimate, Sampler};
+#[cfg(feature = "std")]
+use crate::{cancel, CancelToken, Config, Error};
+
 /// Faster version of estimate_pi.
 ///
 /// It still uses Monte Carlo sampling but no longer supports reporting
@@ -10,22 +13,108 @@ use rand::Rng;
 ///
 /// # Arguments
 ///
-/// * n: a 64-bit positive integer, the number of samples to run
-/// * display: a 64-bit positive integer, the frequency at which to report
-///            the currently estimated value of pi
+/// * N: a 64-bit positive integer, the number of samples to run
+///
+/// Returns NaN if N is zero. `try_estimate_pi_fast` returns an error
+/// instead.
+#[cfg(feature = "std")]
 #[allow(non_snake_case)]
 pub fn estimate_pi_fast(N: u64) -> f64 {
     // Prepare a random number generator we'll use for the whole loop
-    let mut rng = rand::thread_rng();
+    estimate_pi_fast_with_rng(N, &mut rand::thread_rng())
+}
+
+/// Version of estimate_pi_fast which checks its argument first, returning
+/// an error instead of NaN.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+///
+/// # Errors
+///
+/// Returns `Error::ZeroSamples` if N is zero.
+#[cfg(feature = "std")]
+#[allow(non_snake_case)]
+pub fn try_estimate_pi_fast(N: u64) -> Result<f64, Error> {
+    let config = Config::builder().samples(N).build()?;
+    Ok(config.estimate_pi_fast().pi())
+}
+
+/// Version of estimate_pi_fast which takes its random numbers from the
+/// supplied generator instead of the thread-local one.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+/// * rng: the random number generator to take samples from
+#[allow(non_snake_case)]
+pub fn estimate_pi_fast_with_rng<R: Rng + ?Sized>(N: u64, rng: &mut R) -> f64 {
+    // The result - our estimate for pi!
+    estimate_pi_fast_detailed(N, rng).pi()
+}
+
+/// Version of estimate_pi_fast which returns the full `Estimate`, so that
+/// its standard error and confidence interval are available too.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+/// * rng: the random number generator to take samples from
+#[allow(non_snake_case)]
+pub fn estimate_pi_fast_detailed<R: Rng + ?Sized>(N: u64, rng: &mut R) -> Estimate {
+    Estimate::new(count_hits(N, rng), N)
+}
 
+/// Version of estimate_pi_fast_detailed which stops early if `cancel` is
+/// cancelled, returning an `Estimate` of just the samples which were taken.
+///
+/// The samples are taken in chunks, with the token checked before each
+/// one, so a run which isn't cancelled gives the same estimate as
+/// estimate_pi_fast_detailed would from the same generator.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the most samples to run
+/// * rng: the random number generator to take samples from
+/// * cancel: checked every `CancelToken::CHECK_EVERY` samples, to see
+///   whether to stop
+#[cfg(feature = "std")]
+#[allow(non_snake_case)]
+pub fn estimate_pi_fast_cancellable<R: Rng + ?Sized>(N: u64, rng: &mut R, cancel: &CancelToken) -> Estimate {
+    cancel::count_hits_until(N, cancel, |chunk| count_hits(chunk, rng))
+}
+
+/// Version of estimate_pi_fast which takes its points from any `Sampler`,
+/// such as the low-discrepancy `Halton` and `Sobol` sequences.
+///
+/// Quasi-Monte Carlo points are not independent of each other, so the
+/// binomial standard error of an `Estimate` would not apply. This returns
+/// just the estimate for pi; to measure the error of a quasi-Monte Carlo
+/// run, repeat it with several scrambled samplers and compare the results.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+/// * sampler: where to take the points from
+#[allow(non_snake_case)]
+pub fn estimate_pi_fast_with_sampler<S: Sampler + ?Sized>(N: u64, sampler: &mut S) -> f64 {
+    let M = count_hits(N, sampler);
+    4.0 * (M as f64 / N as f64)
+}
+
+/// The loop at the heart of estimate_pi_fast: take N points from `sampler`
+/// and return M, the number which landed inside the quarter-circle.
+#[allow(non_snake_case)]
+pub(crate) fn count_hits<S: Sampler + ?Sized>(N: u64, sampler: &mut S) -> u64 {
     // Initialise the counter
     let mut M: u64 = 0;
 
     // Run the loop n times
     for _ in 0..N {
-        // Get two random numbers from our generator
-        let x: f64 = rng.gen();
-        let y: f64 = rng.gen();
+        // Get the next point. For a random number generator this means
+        // getting two random numbers.
+        let (x, y) = sampler.next_point();
 
         // The same check as before
         if (x*x + y*y) < 1.0 {
@@ -33,6 +122,17 @@ pub fn estimate_pi_fast(N: u64) -> f64 {
         }
     }
 
-    // The result - our estimate for pi!
-    4.0 * (M as f64 / N as f64)
+    M
+}
+
+/// Version of estimate_pi_fast which is reproducible: the same `N` and
+/// `seed` always give the same estimate.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+/// * seed: a 64-bit integer used to initialise the random number generator
+#[allow(non_snake_case)]
+pub fn estimate_pi_fast_seeded(N: u64, seed: u64) -> f64 {
+    estimate_pi_fast_with_rng(N, &mut crate::seeded_rng(seed))
 }
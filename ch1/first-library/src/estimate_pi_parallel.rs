@@ -0,0 +1,109 @@
+use std::thread;
+
+use rand::Rng;
+
+use crate::cancel::count_hits_until;
+use crate::estimate_pi_fast::count_hits;
+use crate::{CancelToken, Config, Error, Estimate};
+
+/// Multi-threaded version of estimate_pi_fast.
+///
+/// The N samples are split as evenly as possible between `threads` worker
+/// threads. Each worker gets its own random number generator, seeded from
+/// a sequence of seeds which is itself generated from `seed`, so the same
+/// `N`, `threads` and `seed` always give the same estimate.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+/// * threads: the number of worker threads to spread the samples across
+/// * seed: a 64-bit integer used to initialise the random number generators
+///
+/// # Panics
+///
+/// Panics if `threads` is zero. `try_estimate_pi_parallel` returns an error
+/// instead.
+#[allow(non_snake_case)]
+pub fn estimate_pi_parallel(N: u64, threads: usize, seed: u64) -> f64 {
+    estimate_pi_parallel_detailed(N, threads, seed).pi()
+}
+
+/// Version of estimate_pi_parallel which checks its arguments first,
+/// returning an error instead of panicking or returning NaN.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+/// * threads: the number of worker threads to spread the samples across
+/// * seed: a 64-bit integer used to initialise the random number generators
+///
+/// # Errors
+///
+/// Returns `Error::ZeroSamples` if N is zero, or `Error::ZeroThreads` if
+/// `threads` is zero.
+#[allow(non_snake_case)]
+pub fn try_estimate_pi_parallel(N: u64, threads: usize, seed: u64) -> Result<f64, Error> {
+    let config = Config::builder().samples(N).threads(threads).seed(seed).build()?;
+    Ok(config.estimate_pi_parallel().pi())
+}
+
+/// Version of estimate_pi_parallel which returns the full `Estimate`, so
+/// that its standard error and confidence interval are available too.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+/// * threads: the number of worker threads to spread the samples across
+/// * seed: a 64-bit integer used to initialise the random number generators
+///
+/// # Panics
+///
+/// Panics if `threads` is zero.
+#[allow(non_snake_case)]
+pub fn estimate_pi_parallel_detailed(N: u64, threads: usize, seed: u64) -> Estimate {
+    estimate_pi_parallel_cancellable(N, threads, seed, &CancelToken::new())
+}
+
+/// Version of estimate_pi_parallel_detailed which stops early if `cancel`
+/// is cancelled. Every worker checks the token, and the `Estimate` holds
+/// the samples they had taken between them when they stopped.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the most samples to run
+/// * threads: the number of worker threads to spread the samples across
+/// * seed: a 64-bit integer used to initialise the random number generators
+/// * cancel: checked every `CancelToken::CHECK_EVERY` samples, to see
+///   whether to stop
+///
+/// # Panics
+///
+/// Panics if `threads` is zero.
+#[allow(non_snake_case)]
+pub fn estimate_pi_parallel_cancellable(N: u64, threads: usize, seed: u64, cancel: &CancelToken) -> Estimate {
+    assert!(threads > 0, "estimate_pi_parallel needs at least one thread");
+
+    // A generator which is only used to pick a seed for each worker.
+    let mut seeds = crate::seeded_rng(seed);
+
+    // Start all of the workers. The first (N % threads) workers take one
+    // extra sample each so that the shares add up to exactly N.
+    let workers = threads as u64;
+    let handles: Vec<_> = (0..workers)
+        .map(|i| {
+            let share = N / workers + if i < N % workers { 1 } else { 0 };
+            let worker_seed: u64 = seeds.gen();
+            let cancel = cancel.clone();
+            thread::spawn(move || {
+                let mut rng = crate::seeded_rng(worker_seed);
+                count_hits_until(share, &cancel, |chunk| count_hits(chunk, &mut rng))
+            })
+        })
+        .collect();
+
+    // Wait for every worker to finish and add up their counts.
+    handles
+        .into_iter()
+        .map(|handle| handle.join().expect("estimate_pi_parallel worker panicked"))
+        .fold(Estimate::new(0, 0), |total, estimate| total + estimate)
+}
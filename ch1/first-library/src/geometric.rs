@@ -0,0 +1,338 @@
+// Other geometric experiments whose outcome depends on pi, so that they can
+// be run backwards to estimate it. Throwing darts at a quarter-circle is
+// the simplest; the others are famous puzzles from probability.
+
+use rand::{Rng, RngCore};
+
+use crate::math;
+use crate::variance_reduction::Moments;
+#[cfg(feature = "std")]
+use crate::CancelToken;
+
+/// A random experiment whose average outcome depends on pi, which can be
+/// repeated many times to estimate it.
+///
+/// Each trial gives a number, and the average of those numbers tends to
+/// a known function of pi, which `pi_from_mean` reverses. The trait takes
+/// the generator as `&mut dyn RngCore` so that estimators can be chosen at
+/// run time and kept together as `Box<dyn GeometricEstimator>`.
+///
+/// None of the experiments use pi itself: where a random angle is needed,
+/// it is taken from a random point in a circle instead.
+pub trait GeometricEstimator {
+    /// A short lowercase name for the estimator.
+    fn name(&self) -> &'static str;
+
+    /// Carry out the experiment once, returning its outcome.
+    fn trial(&self, rng: &mut dyn RngCore) -> f64;
+
+    /// Turn the average outcome of many trials into an estimate of pi.
+    fn pi_from_mean(&self, mean: f64) -> f64;
+}
+
+/// The result of estimate_pi_geometric.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeometricEstimate {
+    /// The estimate for pi
+    pub pi: f64,
+    /// The standard error of the estimate, measured from the trials
+    /// themselves
+    pub standard_error: f64,
+    /// The number of trials
+    pub trials: u64,
+}
+
+impl GeometricEstimate {
+    /// How accurate a single trial is: the standard error scaled up to
+    /// what it would be for one trial. Lower is better, and the ratio
+    /// between two estimators says how many more trials the worse one
+    /// needs for the same accuracy, squared.
+    pub fn spread(&self) -> f64 {
+        self.standard_error * math::sqrt(self.trials as f64)
+    }
+}
+
+/// Estimate pi by repeating a geometric experiment.
+///
+/// # Arguments
+///
+/// * estimator: the experiment to run
+/// * num_trials: a 64-bit positive integer, the number of times to run it
+/// * rng: the random number generator to take samples from
+pub fn estimate_pi_geometric(
+    estimator: &dyn GeometricEstimator,
+    num_trials: u64,
+    rng: &mut dyn RngCore,
+) -> GeometricEstimate {
+    let mut outcomes = Moments::default();
+    for _ in 0..num_trials {
+        outcomes.add(estimator.trial(rng));
+    }
+    estimate_from(estimator, &outcomes, num_trials)
+}
+
+/// Version of estimate_pi_geometric which stops early if `cancel` is
+/// cancelled, checking it every `CancelToken::CHECK_EVERY` trials. The
+/// estimate then comes from just the trials which were carried out.
+///
+/// # Arguments
+///
+/// * estimator: the experiment to run
+/// * num_trials: a 64-bit positive integer, the most times to run it
+/// * rng: the random number generator to take samples from
+/// * cancel: says when to stop early
+#[cfg(feature = "std")]
+pub fn estimate_pi_geometric_cancellable(
+    estimator: &dyn GeometricEstimator,
+    num_trials: u64,
+    rng: &mut dyn RngCore,
+    cancel: &CancelToken,
+) -> GeometricEstimate {
+    let mut outcomes = Moments::default();
+    let mut trials = 0;
+    while trials < num_trials && !cancel.is_cancelled() {
+        let chunk = CancelToken::CHECK_EVERY.min(num_trials - trials);
+        for _ in 0..chunk {
+            outcomes.add(estimator.trial(rng));
+        }
+        trials += chunk;
+    }
+    estimate_from(estimator, &outcomes, trials)
+}
+
+/// Turn the outcomes of `num_trials` trials into an estimate of pi.
+fn estimate_from(estimator: &dyn GeometricEstimator, outcomes: &Moments, num_trials: u64) -> GeometricEstimate {
+    // The error in the mean carries through to pi in proportion to how
+    // steeply pi_from_mean changes there (the "delta method")
+    let mean = outcomes.mean();
+    let step = 1e-6 * mean.abs().max(1e-6);
+    let slope = (estimator.pi_from_mean(mean + step) - estimator.pi_from_mean(mean - step)) / (2.0 * step);
+    let mean_error = math::sqrt(outcomes.variance() / num_trials as f64);
+
+    GeometricEstimate {
+        pi: estimator.pi_from_mean(mean),
+        standard_error: slope.abs() * mean_error,
+        trials: num_trials,
+    }
+}
+
+/// Every geometric estimator, with its default settings, for comparing
+/// them against each other.
+#[cfg(feature = "std")]
+pub fn geometric_estimators() -> Vec<Box<dyn GeometricEstimator>> {
+    vec![
+        Box::new(Dart),
+        Box::new(BuffonNeedle::default()),
+        Box::new(BuffonLaplace::default()),
+        Box::new(RandomChord::Endpoints),
+        Box::new(RandomChord::Radial),
+    ]
+}
+
+/// A random direction, as its cosine and sine.
+///
+/// A point chosen at random from the square around the unit circle, and
+/// kept only if it lands inside the circle, points in a uniformly random
+/// direction. Scaling it to length 1 gives the cosine and sine of that
+/// angle, without needing to know pi to pick the angle.
+fn random_direction(rng: &mut dyn RngCore) -> (f64, f64) {
+    loop {
+        let x = 2.0 * rng.gen::<f64>() - 1.0;
+        let y = 2.0 * rng.gen::<f64>() - 1.0;
+        let length_squared = x*x + y*y;
+        // Points very near the centre have no useful direction
+        if length_squared <= 1.0 && length_squared > 1e-12 {
+            let length = math::sqrt(length_squared);
+            return (x / length, y / length);
+        }
+    }
+}
+
+/// The quarter-circle dart-throwing experiment used by estimate_pi: a
+/// trial scores 1 if a random point in the unit square is within 1 of the
+/// corner, and the average score is pi / 4.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Dart;
+
+impl GeometricEstimator for Dart {
+    fn name(&self) -> &'static str {
+        "dart"
+    }
+
+    fn trial(&self, rng: &mut dyn RngCore) -> f64 {
+        let x: f64 = rng.gen();
+        let y: f64 = rng.gen();
+        if (x*x + y*y) < 1.0 { 1.0 } else { 0.0 }
+    }
+
+    fn pi_from_mean(&self, mean: f64) -> f64 {
+        4.0 * mean
+    }
+}
+
+/// Buffon's needle: drop a needle onto a floor of parallel lines and see
+/// whether it crosses one. For a needle no longer than the gap between
+/// the lines, it does with probability 2 l / (pi t), where l is the length
+/// of the needle and t the gap.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BuffonNeedle {
+    needle_length: f64,
+    line_spacing: f64,
+}
+
+impl BuffonNeedle {
+    /// Create the experiment for a needle of length `needle_length`
+    /// dropped on lines `line_spacing` apart.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless 0 < needle_length <= line_spacing.
+    pub fn new(needle_length: f64, line_spacing: f64) -> BuffonNeedle {
+        assert!(
+            needle_length > 0.0 && needle_length <= line_spacing,
+            "the needle must be no longer than the gap between the lines"
+        );
+        BuffonNeedle {
+            needle_length,
+            line_spacing,
+        }
+    }
+}
+
+impl Default for BuffonNeedle {
+    /// A needle as long as the gap, which crosses a line most often and so
+    /// gives the most accurate estimate.
+    fn default() -> BuffonNeedle {
+        BuffonNeedle::new(1.0, 1.0)
+    }
+}
+
+impl GeometricEstimator for BuffonNeedle {
+    fn name(&self) -> &'static str {
+        "buffon"
+    }
+
+    fn trial(&self, rng: &mut dyn RngCore) -> f64 {
+        // By symmetry we only need the distance from the needle's centre
+        // to the nearest line, and the needle's angle to the lines between
+        // 0 and 90 degrees
+        let distance = rng.gen::<f64>() * self.line_spacing / 2.0;
+        let (_, sin) = random_direction(rng);
+        let reach = self.needle_length / 2.0 * sin.abs();
+        if distance <= reach { 1.0 } else { 0.0 }
+    }
+
+    fn pi_from_mean(&self, mean: f64) -> f64 {
+        2.0 * self.needle_length / (self.line_spacing * mean)
+    }
+}
+
+/// The Buffon–Laplace problem: drop a needle onto a grid of rectangles
+/// and see whether it crosses any of the grid lines. For a needle of
+/// length l no longer than either side of the a by b rectangles, it does
+/// with probability (2 l (a + b) - l^2) / (pi a b).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BuffonLaplace {
+    needle_length: f64,
+    width: f64,
+    height: f64,
+}
+
+impl BuffonLaplace {
+    /// Create the experiment for a needle of length `needle_length`
+    /// dropped on a grid of `width` by `height` rectangles.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless the needle is longer than 0 and no longer than either
+    /// side of the rectangles.
+    pub fn new(needle_length: f64, width: f64, height: f64) -> BuffonLaplace {
+        assert!(
+            needle_length > 0.0 && needle_length <= width && needle_length <= height,
+            "the needle must be no longer than the sides of the rectangles"
+        );
+        BuffonLaplace {
+            needle_length,
+            width,
+            height,
+        }
+    }
+}
+
+impl Default for BuffonLaplace {
+    /// A needle as long as the sides of a square grid.
+    fn default() -> BuffonLaplace {
+        BuffonLaplace::new(1.0, 1.0, 1.0)
+    }
+}
+
+impl GeometricEstimator for BuffonLaplace {
+    fn name(&self) -> &'static str {
+        "buffon-laplace"
+    }
+
+    fn trial(&self, rng: &mut dyn RngCore) -> f64 {
+        // Put the needle's centre somewhere in one rectangle. The grid
+        // looks the same when reflected, so again only angles between 0
+        // and 90 degrees are needed.
+        let x = rng.gen::<f64>() * self.width;
+        let y = rng.gen::<f64>() * self.height;
+        let (cos, sin) = random_direction(rng);
+        let half_x = self.needle_length / 2.0 * cos.abs();
+        let half_y = self.needle_length / 2.0 * sin.abs();
+
+        let crosses_vertical = x < half_x || x + half_x > self.width;
+        let crosses_horizontal = y < half_y || y + half_y > self.height;
+        if crosses_vertical || crosses_horizontal { 1.0 } else { 0.0 }
+    }
+
+    fn pi_from_mean(&self, mean: f64) -> f64 {
+        let l = self.needle_length;
+        (2.0 * l * (self.width + self.height) - l * l) / (self.width * self.height * mean)
+    }
+}
+
+/// Draw a random chord of the unit circle and measure its length.
+///
+/// What "random" means for a chord is famously ambiguous (Bertrand's
+/// paradox): different ways of choosing one give different average
+/// lengths, and so different functions of pi.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RandomChord {
+    /// Join two random points on the circle. The average length is 4 / pi.
+    Endpoints,
+    /// Pick a random point along a radius and draw the chord through it at
+    /// right angles to the radius. The average length is pi / 2.
+    Radial,
+}
+
+impl GeometricEstimator for RandomChord {
+    fn name(&self) -> &'static str {
+        match self {
+            RandomChord::Endpoints => "chord-endpoints",
+            RandomChord::Radial => "chord-radial",
+        }
+    }
+
+    fn trial(&self, rng: &mut dyn RngCore) -> f64 {
+        match self {
+            RandomChord::Endpoints => {
+                let (x1, y1) = random_direction(rng);
+                let (x2, y2) = random_direction(rng);
+                let (dx, dy) = (x2 - x1, y2 - y1);
+                math::sqrt(dx*dx + dy*dy)
+            }
+            RandomChord::Radial => {
+                let distance: f64 = rng.gen();
+                2.0 * math::sqrt(1.0 - distance * distance)
+            }
+        }
+    }
+
+    fn pi_from_mean(&self, mean: f64) -> f64 {
+        match self {
+            RandomChord::Endpoints => 4.0 / mean,
+            RandomChord::Radial => 2.0 * mean,
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use rand::Rng;
+
+use crate::estimate::normal_quantile;
+use crate::{math, CancelToken, NoProgress, Progress};
+
+/// The result of a Monte Carlo integration: the estimated value of the
+/// integral and its standard error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Integral {
+    /// The estimated value of the integral
+    pub value: f64,
+    /// The standard error of `value`, from the sample variance of the
+    /// integrand
+    pub standard_error: f64,
+    /// The number of points the integrand was evaluated at
+    pub samples: u64,
+}
+
+impl Integral {
+    /// How far either side of `value` the true integral is likely to lie,
+    /// at the given confidence level (e.g. 0.95 for 95%).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is not strictly between 0 and 1.
+    pub fn error_bound(&self, level: f64) -> f64 {
+        error_bound(self.standard_error, level)
+    }
+}
+
+/// The result of a Monte Carlo volume estimate: how many of the points
+/// sampled from the box landed inside the region.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Volume {
+    /// The number of points which landed inside the region
+    pub hits: u64,
+    /// The total number of points sampled
+    pub samples: u64,
+    /// The volume of the box the points were sampled from
+    pub box_volume: f64,
+}
+
+impl Volume {
+    /// The estimated volume of the region: the fraction of points which
+    /// landed inside it, times the volume of the box.
+    pub fn value(&self) -> f64 {
+        self.box_volume * (self.hits as f64 / self.samples as f64)
+    }
+
+    /// The standard error of `value`. Each point lands inside the region
+    /// with some probability p, so this comes from the binomial variance
+    /// of the hit count just as it does for an `Estimate` of pi.
+    pub fn standard_error(&self) -> f64 {
+        let p = self.hits as f64 / self.samples as f64;
+        self.box_volume * math::sqrt(p * (1.0 - p) / self.samples as f64)
+    }
+
+    /// How far either side of `value` the true volume is likely to lie,
+    /// at the given confidence level (e.g. 0.95 for 95%).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `level` is not strictly between 0 and 1.
+    pub fn error_bound(&self, level: f64) -> f64 {
+        error_bound(self.standard_error(), level)
+    }
+}
+
+fn error_bound(standard_error: f64, level: f64) -> f64 {
+    assert!(
+        level > 0.0 && level < 1.0,
+        "confidence level must be between 0 and 1, got {}",
+        level
+    );
+    normal_quantile(0.5 + level / 2.0) * standard_error
+}
+
+/// Integrate `f` over an n-dimensional box using Monte Carlo sampling.
+///
+/// The box is given as one `(lower, upper)` pair per dimension, and `f`
+/// receives each point as a slice with one coordinate per dimension.
+///
+/// # Arguments
+///
+/// * f: the function to integrate
+/// * bounds: the lower and upper limit of the box in each dimension
+/// * num_samples: a 64-bit positive integer, the number of points to
+///   evaluate `f` at
+/// * rng: the random number generator to take points from
+pub fn integrate<F, R>(f: F, bounds: &[(f64, f64)], num_samples: u64, rng: &mut R) -> Integral
+where
+    F: FnMut(&[f64]) -> f64,
+    R: Rng + ?Sized,
+{
+    integrate_with_progress(f, bounds, num_samples, num_samples, rng, &mut NoProgress)
+}
+
+/// Version of integrate which reports the number of points sampled and
+/// the current value of the integral to `progress` every `display` points.
+///
+/// # Arguments
+///
+/// * f: the function to integrate
+/// * bounds: the lower and upper limit of the box in each dimension
+/// * num_samples: a 64-bit positive integer, the number of points to
+///   evaluate `f` at
+/// * display: a 64-bit positive integer, the frequency at which to report
+///   the current value of the integral
+/// * rng: the random number generator to take points from
+/// * progress: receives the reports
+pub fn integrate_with_progress<F, R, P>(
+    mut f: F,
+    bounds: &[(f64, f64)],
+    num_samples: u64,
+    display: u64,
+    rng: &mut R,
+    progress: &mut P,
+) -> Integral
+where
+    F: FnMut(&[f64]) -> f64,
+    R: Rng + ?Sized,
+    P: Progress + ?Sized,
+{
+    let box_volume = box_volume(bounds);
+    let mut point = vec![0.0; bounds.len()];
+
+    // Keep a running mean and sum of squared differences from the mean
+    // (Welford's method), which gives us the variance without having to
+    // store every value of f.
+    let mut mean = 0.0;
+    let mut squares = 0.0;
+
+    for n in 1..=num_samples {
+        sample_point(bounds, &mut point, rng);
+        let value = f(&point);
+
+        let delta = value - mean;
+        mean += delta / n as f64;
+        squares += delta * (value - mean);
+
+        if n.is_multiple_of(display) {
+            progress.report(n, box_volume * mean);
+        }
+    }
+
+    // The sample variance of f, and from it the variance of our mean
+    let variance = if num_samples > 1 { squares / (num_samples - 1) as f64 } else { 0.0 };
+
+    Integral {
+        value: box_volume * mean,
+        standard_error: box_volume * math::sqrt(variance / num_samples as f64),
+        samples: num_samples,
+    }
+}
+
+/// Estimate the volume of the region where `indicator` returns true, by
+/// sampling points from an n-dimensional box which contains it.
+///
+/// # Arguments
+///
+/// * indicator: returns whether a point is inside the region
+/// * bounds: the lower and upper limit of the box in each dimension
+/// * num_samples: a 64-bit positive integer, the number of points to test
+/// * rng: the random number generator to take points from
+pub fn volume<F, R>(indicator: F, bounds: &[(f64, f64)], num_samples: u64, rng: &mut R) -> Volume
+where
+    F: FnMut(&[f64]) -> bool,
+    R: Rng + ?Sized,
+{
+    volume_with_progress(indicator, bounds, num_samples, num_samples, rng, &mut NoProgress)
+}
+
+/// Version of volume which reports the number of points sampled and the
+/// current estimate of the volume to `progress` every `display` points.
+///
+/// # Arguments
+///
+/// * indicator: returns whether a point is inside the region
+/// * bounds: the lower and upper limit of the box in each dimension
+/// * num_samples: a 64-bit positive integer, the number of points to test
+/// * display: a 64-bit positive integer, the frequency at which to report
+///   the current estimate of the volume
+/// * rng: the random number generator to take points from
+/// * progress: receives the reports
+pub fn volume_with_progress<F, R, P>(
+    indicator: F,
+    bounds: &[(f64, f64)],
+    num_samples: u64,
+    display: u64,
+    rng: &mut R,
+    progress: &mut P,
+) -> Volume
+where
+    F: FnMut(&[f64]) -> bool,
+    R: Rng + ?Sized,
+    P: Progress + ?Sized,
+{
+    volume_until(indicator, bounds, num_samples, display, rng, progress, &CancelToken::new())
+}
+
+/// Version of volume_with_progress which stops early if `cancel` is
+/// cancelled, returning the points sampled so far.
+pub(crate) fn volume_until<F, R, P>(
+    mut indicator: F,
+    bounds: &[(f64, f64)],
+    num_samples: u64,
+    display: u64,
+    rng: &mut R,
+    progress: &mut P,
+    cancel: &CancelToken,
+) -> Volume
+where
+    F: FnMut(&[f64]) -> bool,
+    R: Rng + ?Sized,
+    P: Progress + ?Sized,
+{
+    let box_volume = box_volume(bounds);
+    let mut point = vec![0.0; bounds.len()];
+    let mut hits = 0;
+    let mut samples = 0;
+
+    for n in 1..=num_samples {
+        // Checking the token takes longer than a sample, so only do it
+        // every so often
+        if (n - 1).is_multiple_of(CancelToken::CHECK_EVERY) && cancel.is_cancelled() {
+            break;
+        }
+
+        sample_point(bounds, &mut point, rng);
+        if indicator(&point) {
+            hits += 1;
+        }
+        samples = n;
+
+        if n.is_multiple_of(display) {
+            progress.report(n, box_volume * (hits as f64 / n as f64));
+        }
+    }
+
+    Volume {
+        hits,
+        samples,
+        box_volume,
+    }
+}
+
+/// The volume of the box, the product of its widths in each dimension.
+fn box_volume(bounds: &[(f64, f64)]) -> f64 {
+    bounds.iter().map(|&(lower, upper)| upper - lower).product()
+}
+
+/// Fill `point` with a random point from the box, one coordinate at a time.
+fn sample_point<R: Rng + ?Sized>(bounds: &[(f64, f64)], point: &mut [f64], rng: &mut R) {
+    for (coordinate, &(lower, upper)) in point.iter_mut().zip(bounds) {
+        let fraction: f64 = rng.gen();
+        *coordinate = lower + (upper - lower) * fraction;
+    }
+}
@@ -0,0 +1,153 @@
+#[cfg(feature = "std")]
+use crate::cancel;
+use crate::deterministic::estimate_pi_deterministic;
+#[cfg(feature = "std")]
+use crate::deterministic::count_hits_integer;
+#[cfg(feature = "std")]
+use crate::estimate_pi_fast::count_hits;
+use crate::estimate_pi_fast::estimate_pi_fast_detailed;
+use crate::pcg::{Pcg32, Pcg32Lanes, LANES};
+#[cfg(feature = "std")]
+use crate::CancelToken;
+use crate::Estimate;
+
+/// The different inner loops estimate_pi_fast can run, so that their speed
+/// can be compared (see `benches/kernels.rs`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kernel {
+    /// The usual loop, with random numbers from the rand crate's StdRng
+    Standard,
+    /// The usual loop, with random numbers from our own `Pcg32`
+    Pcg,
+    /// Tests `LANES` points per iteration, each from its own PCG32 stream,
+    /// in a form the compiler can turn into SIMD instructions
+    Batched,
+    /// The usual loop, with random numbers from our own `Pcg32` and only
+    /// integer arithmetic, so a seed gives the same result on every
+    /// platform (see `estimate_pi_deterministic`)
+    Deterministic,
+}
+
+impl Kernel {
+    /// Every kernel, for comparing them against each other.
+    pub const ALL: [Kernel; 4] = [
+        Kernel::Standard,
+        Kernel::Pcg,
+        Kernel::Batched,
+        Kernel::Deterministic,
+    ];
+
+    /// A short lowercase name for the kernel.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kernel::Standard => "standard",
+            Kernel::Pcg => "pcg",
+            Kernel::Batched => "batched",
+            Kernel::Deterministic => "deterministic",
+        }
+    }
+}
+
+/// Estimate pi from N samples using the chosen kernel, with its random
+/// numbers seeded from `seed`.
+///
+/// The kernels take their random numbers in different ways, so the same
+/// seed gives a different (but equally good) estimate from each one.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of samples to run
+/// * kernel: which inner loop to use
+/// * seed: a 64-bit integer used to initialise the random number generator
+#[allow(non_snake_case)]
+pub fn estimate_pi_fast_kernel(N: u64, kernel: Kernel, seed: u64) -> Estimate {
+    match kernel {
+        Kernel::Standard => estimate_pi_fast_detailed(N, &mut crate::seeded_rng(seed)),
+        Kernel::Pcg => estimate_pi_fast_detailed(N, &mut Pcg32::seeded(seed)),
+        Kernel::Batched => Estimate::new(count_hits_batched(N, &mut Pcg32Lanes::seeded(seed)), N),
+        Kernel::Deterministic => estimate_pi_deterministic(N, seed),
+    }
+}
+
+/// Version of estimate_pi_fast_kernel which stops early if `cancel` is
+/// cancelled, returning an `Estimate` of just the samples which were taken.
+///
+/// Each kernel keeps drawing from the same generator between the checks of
+/// the token, so a run which isn't cancelled gives exactly the same
+/// estimate as estimate_pi_fast_kernel.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the most samples to run
+/// * kernel: which inner loop to use
+/// * seed: a 64-bit integer used to initialise the random number generator
+/// * cancel: checked every `CancelToken::CHECK_EVERY` samples, to see
+///   whether to stop
+#[cfg(feature = "std")]
+#[allow(non_snake_case)]
+pub fn estimate_pi_fast_kernel_cancellable(N: u64, kernel: Kernel, seed: u64, cancel: &CancelToken) -> Estimate {
+    match kernel {
+        Kernel::Standard => {
+            let mut rng = crate::seeded_rng(seed);
+            cancel::count_hits_until(N, cancel, |chunk| count_hits(chunk, &mut rng))
+        }
+        Kernel::Pcg => {
+            let mut rng = Pcg32::seeded(seed);
+            cancel::count_hits_until(N, cancel, |chunk| count_hits(chunk, &mut rng))
+        }
+        Kernel::Batched => {
+            // Every chunk but the last is a whole number of batches, so no
+            // lanes are skipped part way through
+            let mut lanes = Pcg32Lanes::seeded(seed);
+            cancel::count_hits_until(N, cancel, |chunk| count_hits_batched(chunk, &mut lanes))
+        }
+        Kernel::Deterministic => {
+            let mut rng = Pcg32::seeded(seed);
+            cancel::count_hits_until(N, cancel, |chunk| count_hits_integer(chunk, &mut rng))
+        }
+    }
+}
+
+/// The batched kernel: take N samples, LANES at a time, and return M, the
+/// number which landed inside the quarter-circle.
+#[allow(non_snake_case)]
+fn count_hits_batched(N: u64, lanes: &mut Pcg32Lanes) -> u64 {
+    let mut xs = [0; LANES];
+    let mut ys = [0; LANES];
+
+    let mut M: u64 = 0;
+
+    for _ in 0..N / LANES as u64 {
+        lanes.next_u32s(&mut xs);
+        lanes.next_u32s(&mut ys);
+        M += count_inside(&xs, &ys, LANES);
+    }
+
+    // Any samples left over use just the first few lanes of one more batch
+    let remainder = (N % LANES as u64) as usize;
+    if remainder > 0 {
+        lanes.next_u32s(&mut xs);
+        lanes.next_u32s(&mut ys);
+        M += count_inside(&xs, &ys, remainder);
+    }
+
+    M
+}
+
+/// Count how many of the first `count` points are inside the
+/// quarter-circle, with each coordinate given as a 32-bit fraction of 1.
+///
+/// There are no branches here: each lane's test turns into a 0 or 1 which
+/// is added to the total, so every lane can be tested at once.
+fn count_inside(xs: &[u32; LANES], ys: &[u32; LANES], count: usize) -> u64 {
+    // 2^-32, to turn a 32-bit integer into a number between 0 and 1
+    const SCALE: f64 = 1.0 / (1u64 << 32) as f64;
+
+    let mut inside = [0u64; LANES];
+    for ((inside, &x), &y) in inside.iter_mut().zip(xs).zip(ys) {
+        let x = x as f64 * SCALE;
+        let y = y as f64 * SCALE;
+        *inside = ((x*x + y*y) < 1.0) as u64;
+    }
+    inside[..count].iter().sum()
+}
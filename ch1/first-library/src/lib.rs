@@ -1,4 +1,67 @@
-#[allow(non_snake_case)]
+// Without the std feature the library only needs the core language, so
+// it can be embedded anywhere - e.g. a bare WebAssembly module - as long
+// as the caller provides the random number generator.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Use the rand Rng trait (needed for the .gen() function to be available)
+// and the SeedableRng trait (needed to create a generator from a seed).
+use rand::rngs::StdRng;
+#[cfg(feature = "std")]
+use rand::Rng;
+use rand::SeedableRng;
+
+// Invalid input is reported with the Error type, and the Config type
+// makes sure a run's settings are valid before it starts.
+#[cfg(feature = "std")]
+mod config;
+mod error;
+#[cfg(feature = "std")]
+pub use config::{Config, ConfigBuilder};
+pub use error::Error;
+
+// The Estimate type holds the result of a run along with the counts it
+// came from, so that we can also say how accurate it is likely to be.
+mod estimate;
+pub use estimate::Estimate;
+
+// Square roots and logarithms, which come from std, or from libm when the
+// library is built without std.
+mod math;
+
+// General-purpose Monte Carlo integration, of which estimating pi is
+// just one example.
+#[cfg(feature = "std")]
+mod integrate;
+#[cfg(feature = "std")]
+pub use integrate::{integrate, integrate_with_progress, volume, volume_with_progress, Integral, Volume};
+
+// Progress reporting lives in its own module so that printing is just one
+// of the ways to follow a long-running estimate.
+mod progress;
+pub use progress::{NoProgress, Progress};
+#[cfg(feature = "std")]
+pub use progress::PrintProgress;
+
+// Convergence traces in machine-readable formats, for plotting and
+// comparing runs.
+#[cfg(feature = "std")]
+mod trace;
+#[cfg(feature = "std")]
+pub use trace::{json_number, TraceFormat, TraceRecord, TraceWriter};
+
+// Stopping a run early, from another thread or after a time budget, and
+// keeping the samples taken so far.
+#[cfg(feature = "std")]
+mod cancel;
+#[cfg(feature = "std")]
+pub use cancel::CancelToken;
+
+// Pictures of the sampled points, and of the estimate converging, as SVG
+// or PPM images.
+#[cfg(feature = "std")]
+mod render;
+#[cfg(feature = "std")]
+pub use render::{render_samples, ImageFormat, RenderOptions};
 
 /// Estimate pi using Monte Carlo sampling
 ///
@@ -6,42 +69,204 @@
 ///
 /// * num_samples: a 64-bit positive integer, the number of samples to run
 /// * display: a 64-bit positive integer, the frequency at which to report
-///            the currently estimated value of pi
+///   the currently estimated value of pi
+///
+/// # Panics
+///
+/// Panics if `display` is zero. `try_estimate_pi` returns an error instead.
+#[cfg(feature = "std")]
 pub fn estimate_pi(num_samples: u64, display: u64) -> f64 {
-    // Initialise two variables we will use for our working
-    let mut M: u64 = 0;
-    let mut N: u64 = 0;
+    // rand::random() draws from the thread-local generator, so sampling
+    // from thread_rng() behaves exactly as calling random() did.
+    estimate_pi_with_rng(num_samples, display, &mut rand::thread_rng())
+}
 
-    // Initialise the output variable pi
-    let mut pi: f64 = 0.0;
+/// Version of estimate_pi which checks its arguments first, returning an
+/// error instead of panicking, or returning 0.0 for no samples.
+///
+/// # Arguments
+///
+/// * num_samples: a 64-bit positive integer, the number of samples to run
+/// * display: a 64-bit positive integer, the frequency at which to report
+///   the currently estimated value of pi
+///
+/// # Errors
+///
+/// Returns `Error::ZeroSamples` if `num_samples` is zero, or
+/// `Error::ZeroDisplay` if `display` is zero.
+#[cfg(feature = "std")]
+pub fn try_estimate_pi(num_samples: u64, display: u64) -> Result<f64, Error> {
+    let config = Config::builder().samples(num_samples).display(display).build()?;
+    Ok(config.estimate_pi(&mut PrintProgress).pi())
+}
 
-    // Loop num_samples times
-    for _ in 0..num_samples {
-        // Each time increase N
-        N += 1;
+/// Estimate pi using Monte Carlo sampling, drawing the random numbers
+/// from the supplied generator.
+///
+/// Passing a generator created from a fixed seed makes the run fully
+/// reproducible, which is what `estimate_pi_seeded` does.
+///
+/// # Arguments
+///
+/// * num_samples: a 64-bit positive integer, the number of samples to run
+/// * display: a 64-bit positive integer, the frequency at which to report
+///   the currently estimated value of pi
+/// * rng: the random number generator to take samples from
+///
+/// # Panics
+///
+/// Panics if `display` is zero.
+#[cfg(feature = "std")]
+pub fn estimate_pi_with_rng<R: Rng + ?Sized>(num_samples: u64, display: u64, rng: &mut R) -> f64 {
+    estimate_pi_with_progress(num_samples, display, rng, &mut PrintProgress)
+}
 
-        // Prepare two random numbers between 0 and 1
-        let x: f64 = rand::random();
-        let y: f64 = rand::random();
+/// Estimate pi using Monte Carlo sampling, handing the progress reports to
+/// `progress` instead of printing them.
+///
+/// # Arguments
+///
+/// * num_samples: a 64-bit positive integer, the number of samples to run
+/// * display: a 64-bit positive integer, the frequency at which to report
+///   the currently estimated value of pi
+/// * rng: the random number generator to take samples from
+/// * progress: receives the sample count and current estimate every
+///   `display` samples
+///
+/// # Panics
+///
+/// Panics if `display` is zero.
+#[cfg(feature = "std")]
+pub fn estimate_pi_with_progress<R, P>(
+    num_samples: u64,
+    display: u64,
+    rng: &mut R,
+    progress: &mut P,
+) -> f64
+where
+    R: Rng + ?Sized,
+    P: Progress + ?Sized,
+{
+    let estimate = estimate_pi_detailed(num_samples, display, rng, progress);
 
-        // Check if the point (x, y) is inside the quarter-circle
-        // of radius 1
-        if (x*x + y*y) < 1.0 {
-            // If it is, then increase our count M
-            M += 1;
-        }
-
-        // Update our estimate for pi
-        pi = 4.0 * (M as f64 / N as f64);
-
-        // If this is time to display our progress, print some output
-        if (N % display) == 0 {
-            println!("{}: {}", N, pi);
-        }
+    // With no samples there is no estimate, and pi() would be NaN. The
+    // original loop returned the 0.0 it started with, so we still do.
+    if estimate.samples == 0 {
+        0.0
+    } else {
+        estimate.pi()
     }
+}
 
-    // Return the value of pi from the function
-    pi
+/// Estimate pi using Monte Carlo sampling, returning the full `Estimate`
+/// so that its standard error and confidence interval are available too.
+///
+/// # Arguments
+///
+/// * num_samples: a 64-bit positive integer, the number of samples to run
+/// * display: a 64-bit positive integer, the frequency at which to report
+///   the currently estimated value of pi
+/// * rng: the random number generator to take samples from
+/// * progress: receives the sample count and current estimate every
+///   `display` samples
+///
+/// # Panics
+///
+/// Panics if `display` is zero.
+#[cfg(feature = "std")]
+pub fn estimate_pi_detailed<R, P>(
+    num_samples: u64,
+    display: u64,
+    rng: &mut R,
+    progress: &mut P,
+) -> Estimate
+where
+    R: Rng + ?Sized,
+    P: Progress + ?Sized,
+{
+    estimate_pi_cancellable(num_samples, display, rng, progress, &CancelToken::new())
+}
+
+/// Version of estimate_pi_detailed which stops early if `cancel` is
+/// cancelled, e.g. from another thread or by its time budget running out.
+/// The `Estimate` then holds just the samples which were taken.
+///
+/// # Arguments
+///
+/// * num_samples: a 64-bit positive integer, the most samples to run
+/// * display: a 64-bit positive integer, the frequency at which to report
+///   the currently estimated value of pi
+/// * rng: the random number generator to take samples from
+/// * progress: receives the sample count and current estimate every
+///   `display` samples
+/// * cancel: checked every `CancelToken::CHECK_EVERY` samples, to see
+///   whether to stop
+///
+/// # Panics
+///
+/// Panics if `display` is zero.
+#[cfg(feature = "std")]
+pub fn estimate_pi_cancellable<R, P>(
+    num_samples: u64,
+    display: u64,
+    rng: &mut R,
+    progress: &mut P,
+    cancel: &CancelToken,
+) -> Estimate
+where
+    R: Rng + ?Sized,
+    P: Progress + ?Sized,
+{
+    // Every function in the estimate_pi family ends up here, so they all
+    // treat a display interval of 0 the same way
+    assert!(display > 0, "display must be positive");
+
+    // The quarter-circle of radius 1 takes up pi / 4 of the unit square,
+    // so estimating its area and multiplying by 4 gives us pi. Progress
+    // reports get the same treatment on their way through.
+    let mut report_pi = |samples, area| progress.report(samples, 4.0 * area);
+    let area = integrate::volume_until(
+        // Check if the point (x, y) is inside the quarter-circle
+        |point| {
+            let (x, y) = (point[0], point[1]);
+            (x*x + y*y) < 1.0
+        },
+        // Sample x and y from between 0 and 1
+        &[(0.0, 1.0), (0.0, 1.0)],
+        num_samples,
+        display,
+        rng,
+        &mut report_pi,
+        cancel,
+    );
+
+    // Return the counts, from which the estimate for pi and its error
+    // can be worked out
+    Estimate::new(area.hits, area.samples)
+}
+
+/// Estimate pi using Monte Carlo sampling from a generator seeded with
+/// `seed`. Two runs with the same arguments always give the same result.
+///
+/// # Arguments
+///
+/// * num_samples: a 64-bit positive integer, the number of samples to run
+/// * display: a 64-bit positive integer, the frequency at which to report
+///   the currently estimated value of pi
+/// * seed: a 64-bit integer used to initialise the random number generator
+///
+/// # Panics
+///
+/// Panics if `display` is zero.
+#[cfg(feature = "std")]
+pub fn estimate_pi_seeded(num_samples: u64, display: u64, seed: u64) -> f64 {
+    estimate_pi_with_rng(num_samples, display, &mut seeded_rng(seed))
+}
+
+/// Create the random number generator used by all the `_seeded` functions
+/// in this library.
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
 }
 
 // These aren't in the print reproduction. `estimate_pi_fast` is an
@@ -49,4 +274,100 @@ pub fn estimate_pi(num_samples: u64, display: u64) -> f64 {
 // to aid performance, but at the cost of needing a little more
 // Rust knowledge (traits) than we want to introduce in Chapter 1.
 mod estimate_pi_fast;
-pub use estimate_pi_fast::estimate_pi_fast;
+pub use estimate_pi_fast::{
+    estimate_pi_fast_detailed, estimate_pi_fast_seeded, estimate_pi_fast_with_rng,
+    estimate_pi_fast_with_sampler,
+};
+#[cfg(feature = "std")]
+pub use estimate_pi_fast::{estimate_pi_fast, estimate_pi_fast_cancellable, try_estimate_pi_fast};
+
+// Different ways of choosing the points estimate_pi_fast tests: random
+// numbers, or the more evenly spread quasi-Monte Carlo sequences.
+mod sampler;
+pub use sampler::{Halton, Sampler, Sobol};
+
+// Our own random number generator, and a choice of inner loops for
+// estimate_pi_fast to compare the speed of.
+mod kernel;
+mod pcg;
+pub use kernel::{estimate_pi_fast_kernel, Kernel};
+#[cfg(feature = "std")]
+pub use kernel::estimate_pi_fast_kernel_cancellable;
+pub use pcg::{Pcg32, LANES};
+
+// An estimator which gives exactly the same result for a given seed on
+// every platform, including WebAssembly.
+mod deterministic;
+pub use deterministic::estimate_pi_deterministic;
+
+// Saving a long deterministic run to a file as it goes, so that it can be
+// resumed if it is interrupted.
+#[cfg(feature = "std")]
+mod checkpoint;
+#[cfg(feature = "std")]
+pub use checkpoint::{estimate_pi_checkpointed, estimate_pi_checkpointed_cancellable, Checkpoint};
+
+// Spreading a run across worker processes which talk to a coordinator
+// over pipes or sockets, using a small line-based protocol.
+#[cfg(feature = "std")]
+mod distributed;
+#[cfg(feature = "std")]
+pub use distributed::{run_worker, worker_reply, Coordinator, Job, Message};
+
+// A multi-threaded version of estimate_pi_fast, for when there are many
+// samples to get through and several cores to run them on.
+#[cfg(feature = "std")]
+mod estimate_pi_parallel;
+#[cfg(feature = "std")]
+pub use estimate_pi_parallel::{
+    estimate_pi_parallel, estimate_pi_parallel_cancellable, estimate_pi_parallel_detailed,
+    try_estimate_pi_parallel,
+};
+
+// An estimator which keeps sampling until the estimate is good enough,
+// instead of needing to know the number of samples up front.
+#[cfg(feature = "std")]
+mod estimate_pi_adaptive;
+#[cfg(feature = "std")]
+pub use estimate_pi_adaptive::{
+    estimate_pi_adaptive, try_estimate_pi_adaptive, AdaptiveEstimate, AdaptiveOptions, StopReason,
+};
+
+// An estimate which can be built up across many calls, and combined with
+// estimates made elsewhere.
+mod pi_estimator;
+pub use pi_estimator::PiEstimator;
+
+// Smarter ways of sampling which give a tighter estimate of pi from the
+// same number of samples.
+mod variance_reduction;
+pub use variance_reduction::{
+    estimate_pi_reduced, try_estimate_pi_reduced, ReducedEstimate, VarianceReduction,
+};
+
+// Other experiments which can be run backwards to estimate pi, such as
+// Buffon's needle, all behind one trait so they can be compared.
+mod geometric;
+#[cfg(feature = "std")]
+pub use geometric::{estimate_pi_geometric_cancellable, geometric_estimators};
+pub use geometric::{
+    estimate_pi_geometric, BuffonLaplace, BuffonNeedle, Dart, GeometricEstimate, GeometricEstimator,
+    RandomChord,
+};
+
+// The volume of the unit ball in any number of dimensions, of which the
+// quarter-circle is the 2-dimensional case.
+#[cfg(feature = "std")]
+mod ball;
+#[cfg(feature = "std")]
+pub use ball::{estimate_ball_volume, unit_ball_volume, BallVolume};
+
+// Deterministic series for pi, which can produce as many exact digits as
+// we like and so can be used to check the estimators above. They need
+// numbers far bigger than a u64, which the bigint module provides.
+#[cfg(feature = "std")]
+mod bigint;
+#[cfg(feature = "std")]
+mod series;
+#[cfg(feature = "std")]
+pub use series::{correct_digits, leibniz, machin, pi_digits_machin, pi_digits_spigot};
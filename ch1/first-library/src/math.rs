@@ -0,0 +1,27 @@
+// The floating point functions the estimators need beyond + - * /.
+// std provides these as methods on f64, but `no_std` builds don't have
+// them, so there they come from the libm crate instead.
+
+/// The square root of x.
+#[cfg(feature = "std")]
+pub(crate) fn sqrt(x: f64) -> f64 {
+    x.sqrt()
+}
+
+/// The square root of x.
+#[cfg(not(feature = "std"))]
+pub(crate) fn sqrt(x: f64) -> f64 {
+    libm::sqrt(x)
+}
+
+/// The natural logarithm of x.
+#[cfg(feature = "std")]
+pub(crate) fn ln(x: f64) -> f64 {
+    x.ln()
+}
+
+/// The natural logarithm of x.
+#[cfg(not(feature = "std"))]
+pub(crate) fn ln(x: f64) -> f64 {
+    libm::log(x)
+}
@@ -0,0 +1,155 @@
+use rand::{Error, RngCore, SeedableRng};
+
+/// The PCG32 random number generator (PCG-XSH-RR with 64 bits of state),
+/// by Melissa O'Neill. See <http://www.pcg-random.org>.
+///
+/// It is small, fast and needs nothing from the operating system, so it
+/// works anywhere - including WebAssembly modules with no source of
+/// entropy. Its output depends only on the seed, so the same seed gives
+/// the same numbers on every platform.
+///
+/// It is not suitable for cryptography.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pcg32 {
+    state: u64,
+    increment: u64,
+}
+
+/// The multiplier of PCG's underlying linear congruential generator.
+const MULTIPLIER: u64 = 6364136223846793005;
+
+impl Pcg32 {
+    /// Create a generator from a seed and a stream number. Generators with
+    /// different stream numbers produce different sequences, even when
+    /// given the same seed.
+    pub fn new(seed: u64, stream: u64) -> Pcg32 {
+        // This is the initialisation from the reference implementation
+        let mut rng = Pcg32 {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    /// Create a generator from a seed, using the default stream.
+    pub fn seeded(seed: u64) -> Pcg32 {
+        Pcg32::new(seed, 0)
+    }
+
+    /// The generator's internal state and increment, from which `from_parts`
+    /// can recreate it, e.g. when saving a checkpoint.
+    #[cfg(feature = "std")]
+    pub(crate) fn to_parts(&self) -> (u64, u64) {
+        (self.state, self.increment)
+    }
+
+    /// Recreate a generator from the values `to_parts` returned. It carries
+    /// on with exactly the numbers the original would have produced next.
+    #[cfg(feature = "std")]
+    pub(crate) fn from_parts(state: u64, increment: u64) -> Pcg32 {
+        Pcg32 { state, increment }
+    }
+
+    /// Move the underlying linear congruential generator on one step.
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+
+    /// Scramble a state into 32 bits of output: an xorshift of the high
+    /// bits, followed by a random rotation.
+    fn output(state: u64) -> u32 {
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+        let rotation = (state >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+}
+
+impl RngCore for Pcg32 {
+    fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        Pcg32::output(old)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // Low half first, as the rand crate's own generators do
+        let low = self.next_u32() as u64;
+        let high = self.next_u32() as u64;
+        (high << 32) | low
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = self.next_u32().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for Pcg32 {
+    /// The first 8 bytes are the seed and the last 8 the stream number,
+    /// both little-endian.
+    type Seed = [u8; 16];
+
+    fn from_seed(seed: [u8; 16]) -> Pcg32 {
+        let mut state = [0; 8];
+        let mut stream = [0; 8];
+        state.copy_from_slice(&seed[..8]);
+        stream.copy_from_slice(&seed[8..]);
+        Pcg32::new(u64::from_le_bytes(state), u64::from_le_bytes(stream))
+    }
+}
+
+/// The number of points the batched kernel tests in each iteration.
+pub const LANES: usize = 8;
+
+/// LANES independent PCG32 generators stepped in lockstep, one per lane of
+/// the batched kernel.
+///
+/// Every lane does exactly the same arithmetic on its own state, so the
+/// compiler can turn the loops over lanes into SIMD instructions where
+/// the target has them.
+#[derive(Clone, Debug)]
+pub(crate) struct Pcg32Lanes {
+    states: [u64; LANES],
+    increments: [u64; LANES],
+}
+
+impl Pcg32Lanes {
+    /// Create the lanes from a seed. Lane i uses stream number i, so the
+    /// lanes are all different from each other.
+    pub(crate) fn seeded(seed: u64) -> Pcg32Lanes {
+        let mut lanes = Pcg32Lanes {
+            states: [0; LANES],
+            increments: [0; LANES],
+        };
+        let lanes_mut = lanes.states.iter_mut().zip(lanes.increments.iter_mut());
+        for (lane, (state, increment)) in lanes_mut.enumerate() {
+            let rng = Pcg32::new(seed, lane as u64);
+            *state = rng.state;
+            *increment = rng.increment;
+        }
+        lanes
+    }
+
+    /// Fill `out` with the next number from every lane.
+    pub(crate) fn next_u32s(&mut self, out: &mut [u32; LANES]) {
+        let lanes = self.states.iter_mut().zip(&self.increments).zip(out.iter_mut());
+        for ((state, &increment), out) in lanes {
+            let old = *state;
+            *state = old.wrapping_mul(MULTIPLIER).wrapping_add(increment);
+            *out = Pcg32::output(old);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+#[cfg(feature = "std")]
+use rand::FromEntropy;
+
+use crate::estimate_pi_fast::count_hits;
+use crate::Estimate;
+
+/// An estimate of pi which can be built up a bit at a time.
+///
+/// Unlike the estimate_pi functions, which take all of their samples in one
+/// go and then return, a PiEstimator keeps its hit and sample counts
+/// between calls. More samples can be added whenever convenient and the
+/// current estimate read at any time.
+///
+/// Partial results from other threads, processes or WebAssembly workers
+/// can be combined with `merge` (or `merge_estimate`, when all we have is
+/// their counts), as long as each one used an independent generator.
+#[derive(Clone, Debug)]
+pub struct PiEstimator<R = StdRng> {
+    rng: R,
+    estimate: Estimate,
+}
+
+impl PiEstimator<StdRng> {
+    /// Create a PiEstimator with a generator seeded with `seed`, so that
+    /// the same sequence of calls always gives the same estimates.
+    pub fn seeded(seed: u64) -> PiEstimator<StdRng> {
+        PiEstimator::new(crate::seeded_rng(seed))
+    }
+
+    /// Create a PiEstimator with a generator seeded from the operating
+    /// system's source of randomness.
+    #[cfg(feature = "std")]
+    pub fn from_entropy() -> PiEstimator<StdRng> {
+        PiEstimator::new(StdRng::from_entropy())
+    }
+}
+
+impl<R: Rng> PiEstimator<R> {
+    /// Create a PiEstimator which has taken no samples yet, and which will
+    /// draw its samples from `rng`.
+    pub fn new(rng: R) -> PiEstimator<R> {
+        PiEstimator {
+            rng,
+            estimate: Estimate::new(0, 0),
+        }
+    }
+
+    /// Take `n` more samples and add them to the running counts.
+    pub fn add_samples(&mut self, n: u64) {
+        let hits = count_hits(n, &mut self.rng);
+        self.merge_estimate(Estimate::new(hits, n));
+    }
+
+    /// Add the counts from another PiEstimator to this one.
+    pub fn merge<S>(&mut self, other: &PiEstimator<S>) {
+        self.merge_estimate(other.estimate);
+    }
+
+    /// Add the counts from an `Estimate` made elsewhere to this one.
+    pub fn merge_estimate(&mut self, other: Estimate) {
+        self.estimate += other;
+    }
+
+    /// The counts so far, from which the estimate and its error can be
+    /// worked out.
+    pub fn estimate(&self) -> Estimate {
+        self.estimate
+    }
+
+    /// The current estimate for pi. This is NaN until some samples have
+    /// been taken.
+    pub fn pi(&self) -> f64 {
+        self.estimate.pi()
+    }
+
+    /// The number of samples taken so far, including merged ones.
+    pub fn samples(&self) -> u64 {
+        self.estimate.samples
+    }
+}
@@ -0,0 +1,37 @@
+/// Something which wants to hear how a long-running estimate is going.
+///
+/// `estimate_pi_with_progress` calls `report` every `display` samples. Any
+/// closure taking the sample count and the current estimate can be used as
+/// a `Progress`, so a GUI, a WebAssembly host or a log file can receive the
+/// updates instead of them being printed.
+pub trait Progress {
+    /// Receive the number of samples taken so far and the current
+    /// estimate for pi.
+    fn report(&mut self, samples: u64, pi: f64);
+}
+
+impl<F: FnMut(u64, f64)> Progress for F {
+    fn report(&mut self, samples: u64, pi: f64) {
+        self(samples, pi)
+    }
+}
+
+/// Prints each report to stdout as a line of the form `samples: pi`.
+///
+/// This is what estimate_pi has always done.
+#[cfg(feature = "std")]
+pub struct PrintProgress;
+
+#[cfg(feature = "std")]
+impl Progress for PrintProgress {
+    fn report(&mut self, samples: u64, pi: f64) {
+        println!("{}: {}", samples, pi);
+    }
+}
+
+/// Throws every report away, for when no progress output is wanted.
+pub struct NoProgress;
+
+impl Progress for NoProgress {
+    fn report(&mut self, _samples: u64, _pi: f64) {}
+}
@@ -0,0 +1,310 @@
+// Pictures of the points an estimator tests, for teaching and for checking
+// by eye that a sampler covers the square evenly. Everything is drawn by
+// hand into either SVG (text, for the web) or PPM (the simplest raster
+// format there is), so no graphics libraries are needed.
+
+use std::f64::consts::PI;
+use std::io::{self, Write};
+
+use crate::Sampler;
+
+/// The image formats a picture can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Scalable Vector Graphics, which browsers can show directly
+    Svg,
+    /// The binary Portable Pixmap format, which most image tools can read
+    Ppm,
+}
+
+/// How to draw a picture of sampled points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// The width and height in pixels of the unit square (and of the
+    /// convergence plot, if there is one)
+    pub size: u32,
+    /// Whether to draw a plot of the estimate against N next to the points
+    pub convergence: bool,
+    /// The format to write the picture in
+    pub format: ImageFormat,
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions {
+            size: 400,
+            convergence: false,
+            format: ImageFormat::Svg,
+        }
+    }
+}
+
+/// An RGB colour.
+type Colour = [u8; 3];
+
+const BACKGROUND: Colour = [255, 255, 255];
+const INSIDE: Colour = [31, 119, 180];
+const OUTSIDE: Colour = [255, 127, 14];
+const OUTLINE: Colour = [64, 64, 64];
+const TRUE_PI: Colour = [214, 39, 40];
+const ESTIMATE: Colour = [0, 0, 0];
+
+/// Draw `num_points` points from `sampler` in the unit square, coloured by
+/// whether they are inside the quarter-circle, and write the picture to
+/// `out`.
+///
+/// In an SVG every point is a separate element, so pictures of more than
+/// a few tens of thousands of points are better written as PPM, which is
+/// drawn pixel by pixel as the points come in and takes the same memory
+/// however many there are.
+///
+/// # Arguments
+///
+/// * num_points: a 64-bit integer, the number of points to draw
+/// * sampler: where to take the points from
+/// * options: the size and format of the picture, and whether to add a
+///   convergence plot
+/// * out: where to write the picture
+pub fn render_samples<S, W>(
+    num_points: u64,
+    sampler: &mut S,
+    options: &RenderOptions,
+    out: W,
+) -> io::Result<()>
+where
+    S: Sampler + ?Sized,
+    W: Write,
+{
+    let size = options.size as f64;
+    let width = if options.convergence { 2 * options.size } else { options.size };
+    let mut picture = Picture::new(options.format, width, options.size);
+
+    // Points in the unit square go from (0, 0) at the bottom left to
+    // (1, 1) at the top right, but pixels count down from the top
+    let to_pixel = |x: f64, y: f64| (x * size, (1.0 - y) * size);
+    let dot_radius = (size / 250.0).max(0.5);
+
+    // The running estimate, recorded at gradually wider intervals so that
+    // the plot has a similar number of points at every scale of N
+    let mut convergence = Vec::new();
+    let mut next_record = 1;
+    let mut hits: u64 = 0;
+
+    for n in 1..=num_points {
+        let (x, y) = sampler.next_point();
+        let inside = (x*x + y*y) < 1.0;
+        if inside {
+            hits += 1;
+        }
+
+        let colour = if inside { INSIDE } else { OUTSIDE };
+        picture.dot(to_pixel(x, y), dot_radius, colour);
+
+        if options.convergence && n >= next_record {
+            convergence.push((n, 4.0 * hits as f64 / n as f64));
+            next_record = (n + 1).max((n as f64 * 1.05) as u64);
+        }
+    }
+
+    // The quarter-circle and the square around it
+    let arc: Vec<_> = (0..=90)
+        .map(|step| {
+            let angle = step as f64 / 90.0 * PI / 2.0;
+            to_pixel(angle.cos(), angle.sin())
+        })
+        .collect();
+    picture.polyline(&arc, OUTLINE);
+    picture.square(0.0, size, OUTLINE);
+
+    if options.convergence {
+        draw_convergence(&mut picture, &convergence, num_points, size);
+    }
+
+    picture.write(out)
+}
+
+/// Plot the estimates against N, on a logarithmic scale, in a square to
+/// the right of the points, with a line across at the true value of pi.
+fn draw_convergence(picture: &mut Picture, convergence: &[(u64, f64)], num_points: u64, size: f64) {
+    // Zoom in as far as the estimates allow, ignoring the first few which
+    // are bound to be a long way out
+    let furthest = convergence
+        .iter()
+        .filter(|&&(n, _)| n >= 10)
+        .map(|&(_, pi)| (pi - PI).abs())
+        .fold(0.05, f64::max);
+
+    let log_span = (num_points as f64).ln();
+    let to_pixel = |n: u64, pi: f64| {
+        let x = if log_span > 0.0 { (n as f64).ln() / log_span } else { 0.0 };
+        let y = 0.5 - 0.45 * (pi - PI) / furthest;
+        (size + x * size, y.clamp(0.0, 1.0) * size)
+    };
+
+    picture.line(to_pixel(1, PI), to_pixel(num_points, PI), TRUE_PI);
+    let points: Vec<_> = convergence.iter().map(|&(n, pi)| to_pixel(n, pi)).collect();
+    picture.polyline(&points, ESTIMATE);
+    picture.square(size, size, OUTLINE);
+}
+
+/// Something to draw in an SVG, in pixel coordinates.
+enum Shape {
+    Dot { centre: (f64, f64), radius: f64, colour: Colour },
+    Line { from: (f64, f64), to: (f64, f64), colour: Colour },
+}
+
+/// Shapes on a white background. For SVG they're kept as a list to write
+/// out at the end, and for PPM they're drawn straight into the pixels, so
+/// that a picture of millions of points doesn't have to remember them all.
+enum Picture {
+    Svg { width: u32, height: u32, shapes: Vec<Shape> },
+    Ppm(Canvas),
+}
+
+impl Picture {
+    fn new(format: ImageFormat, width: u32, height: u32) -> Picture {
+        match format {
+            ImageFormat::Svg => Picture::Svg {
+                width,
+                height,
+                shapes: Vec::new(),
+            },
+            ImageFormat::Ppm => Picture::Ppm(Canvas::new(width, height)),
+        }
+    }
+
+    fn dot(&mut self, centre: (f64, f64), radius: f64, colour: Colour) {
+        match self {
+            Picture::Svg { shapes, .. } => shapes.push(Shape::Dot { centre, radius, colour }),
+            Picture::Ppm(canvas) => canvas.dot(centre, radius, colour),
+        }
+    }
+
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), colour: Colour) {
+        match self {
+            Picture::Svg { shapes, .. } => shapes.push(Shape::Line { from, to, colour }),
+            Picture::Ppm(canvas) => canvas.line(from, to, colour),
+        }
+    }
+
+    fn polyline(&mut self, points: &[(f64, f64)], colour: Colour) {
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], colour);
+        }
+    }
+
+    /// The outline of a square with its top left corner at (left, 0).
+    fn square(&mut self, left: f64, size: f64, colour: Colour) {
+        // Keep the right and bottom edges on the last row of pixels
+        let (right, bottom) = (left + size - 1.0, size - 1.0);
+        let corners = [(left, 0.0), (right, 0.0), (right, bottom), (left, bottom), (left, 0.0)];
+        self.polyline(&corners, colour);
+    }
+
+    fn write<W: Write>(&self, out: W) -> io::Result<()> {
+        match self {
+            Picture::Svg { width, height, shapes } => write_svg(*width, *height, shapes, out),
+            Picture::Ppm(canvas) => canvas.write_ppm(out),
+        }
+    }
+}
+
+/// Write out the shapes as SVG elements, one per line.
+fn write_svg<W: Write>(width: u32, height: u32, shapes: &[Shape], mut out: W) -> io::Result<()> {
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+        width, height
+    )?;
+    writeln!(out, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", hex(BACKGROUND))?;
+    for shape in shapes {
+        match *shape {
+            Shape::Dot { centre, radius, colour } => writeln!(
+                out,
+                "<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\" fill=\"{}\"/>",
+                centre.0,
+                centre.1,
+                radius,
+                hex(colour)
+            )?,
+            Shape::Line { from, to, colour } => writeln!(
+                out,
+                "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\" stroke=\"{}\"/>",
+                from.0,
+                from.1,
+                to.0,
+                to.1,
+                hex(colour)
+            )?,
+        }
+    }
+    writeln!(out, "</svg>")
+}
+
+/// A colour as an SVG hex code, e.g. #ff7f0e.
+fn hex(colour: Colour) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+/// A grid of pixels to draw into for PPM.
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<Colour>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Canvas {
+        Canvas {
+            width,
+            height,
+            pixels: vec![BACKGROUND; width as usize * height as usize],
+        }
+    }
+
+    /// Colour the pixel which contains the point (x, y), if it's on the
+    /// canvas.
+    fn set(&mut self, x: f64, y: f64, colour: Colour) {
+        let (x, y) = (x.floor(), y.floor());
+        if x >= 0.0 && y >= 0.0 && x < self.width as f64 && y < self.height as f64 {
+            self.pixels[y as usize * self.width as usize + x as usize] = colour;
+        }
+    }
+
+    /// Colour every pixel whose centre is within the circle, and always
+    /// the one the centre is in, so that small dots don't vanish.
+    fn dot(&mut self, centre: (f64, f64), radius: f64, colour: Colour) {
+        self.set(centre.0, centre.1, colour);
+        let (left, right) = ((centre.0 - radius).floor(), (centre.0 + radius).ceil());
+        let (top, bottom) = ((centre.1 - radius).floor(), (centre.1 + radius).ceil());
+        let mut y = top;
+        while y <= bottom {
+            let mut x = left;
+            while x <= right {
+                let (dx, dy) = (x + 0.5 - centre.0, y + 0.5 - centre.1);
+                if dx*dx + dy*dy <= radius * radius {
+                    self.set(x, y, colour);
+                }
+                x += 1.0;
+            }
+            y += 1.0;
+        }
+    }
+
+    fn write_ppm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let bytes: Vec<u8> = self.pixels.iter().flatten().copied().collect();
+        out.write_all(&bytes)
+    }
+
+    /// Colour the pixels along a line, stepping half a pixel at a time so
+    /// that there are no gaps.
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), colour: Colour) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let steps = (dx.abs().max(dy.abs()) * 2.0).ceil().max(1.0) as u32;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            self.set(from.0 + t * dx, from.1 + t * dy, colour);
+        }
+    }
+}
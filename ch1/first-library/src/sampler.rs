@@ -0,0 +1,204 @@
+use rand::Rng;
+
+/// A source of points in the unit square, for the estimation loop in
+/// estimate_pi_fast to test against the quarter-circle.
+///
+/// Every random number generator is a Sampler which takes x and then y
+/// from the generator. The low-discrepancy sequences in this module are
+/// Samplers too: they spread their points out much more evenly than random
+/// ones do, so the estimate converges at close to O(1/N) instead of
+/// O(1/sqrt(N)). This is known as quasi-Monte Carlo.
+pub trait Sampler {
+    /// Produce the next point (x, y), with both coordinates in [0, 1).
+    fn next_point(&mut self) -> (f64, f64);
+}
+
+impl<R: Rng + ?Sized> Sampler for R {
+    fn next_point(&mut self) -> (f64, f64) {
+        let x: f64 = self.gen();
+        let y: f64 = self.gen();
+        (x, y)
+    }
+}
+
+/// The two-dimensional Halton sequence, which uses the van der Corput
+/// sequences in bases 2 and 3 for x and y.
+///
+/// The plain sequence starts from the point (0, 0). The scrambled version
+/// adds a random shift to each coordinate (wrapping around at 1), known as
+/// a Cranley-Patterson rotation. The points stay just as evenly spread but
+/// different shifts give independent estimates, which lets us measure
+/// the error of a quasi-Monte Carlo run.
+#[derive(Clone, Debug)]
+pub struct Halton {
+    index: u64,
+    shift: (f64, f64),
+}
+
+impl Halton {
+    /// Create an unscrambled Halton sequence.
+    pub fn new() -> Halton {
+        Halton {
+            index: 0,
+            shift: (0.0, 0.0),
+        }
+    }
+
+    /// Create a Halton sequence randomly shifted using `rng`.
+    pub fn scrambled<R: Rng + ?Sized>(rng: &mut R) -> Halton {
+        Halton {
+            index: 0,
+            shift: (rng.gen(), rng.gen()),
+        }
+    }
+}
+
+impl Default for Halton {
+    fn default() -> Halton {
+        Halton::new()
+    }
+}
+
+impl Sampler for Halton {
+    fn next_point(&mut self) -> (f64, f64) {
+        let x = radical_inverse(self.index, 2);
+        let y = radical_inverse(self.index, 3);
+        self.index += 1;
+        (wrap(x + self.shift.0), wrap(y + self.shift.1))
+    }
+}
+
+/// The van der Corput radical inverse: write `index` in `base`, then
+/// mirror its digits about the decimal point. E.g. in base 2, 6 = 110
+/// becomes 0.011 = 0.375.
+fn radical_inverse(mut index: u64, base: u64) -> f64 {
+    let mut result = 0.0;
+    let mut scale = 1.0 / base as f64;
+    while index > 0 {
+        result += (index % base) as f64 * scale;
+        index /= base;
+        scale /= base as f64;
+    }
+    result
+}
+
+/// Wrap a number in [0, 2) back into [0, 1).
+fn wrap(value: f64) -> f64 {
+    if value >= 1.0 {
+        value - 1.0
+    } else {
+        value
+    }
+}
+
+/// The number of bits of precision in each Sobol coordinate.
+const SOBOL_BITS: usize = 32;
+
+/// The two-dimensional Sobol sequence.
+///
+/// The x coordinate is the van der Corput sequence in base 2. The y
+/// coordinate uses the direction numbers from the primitive polynomial
+/// x + 1. Points are generated in Gray code order, so each one needs just
+/// a single XOR. The sequence starts from (0, 0) and has 2^32 points.
+///
+/// The scrambled version XORs every point with a random bit pattern (a
+/// "digital shift"). This keeps the points evenly spread while making
+/// estimates from different shifts independent.
+#[derive(Clone, Debug)]
+pub struct Sobol {
+    index: u64,
+    current: [u32; 2],
+    shift: [u32; 2],
+    directions: [[u32; SOBOL_BITS]; 2],
+}
+
+impl Sobol {
+    /// Create an unscrambled Sobol sequence.
+    pub fn new() -> Sobol {
+        let mut directions = [[0; SOBOL_BITS]; 2];
+
+        // First dimension: the kth direction number is just the kth bit
+        // after the binary point.
+        for (k, v) in directions[0].iter_mut().enumerate() {
+            *v = 1 << (SOBOL_BITS - 1 - k);
+        }
+
+        // Second dimension: for the polynomial x + 1 the recurrence for
+        // the direction integers is m_k = 2 m_(k-1) XOR m_(k-1), with
+        // m_1 = 1. The kth direction number is m_k shifted into place.
+        let mut m: u32 = 1;
+        for (k, v) in directions[1].iter_mut().enumerate() {
+            if k > 0 {
+                m ^= m << 1;
+            }
+            *v = m << (SOBOL_BITS - 1 - k);
+        }
+
+        Sobol {
+            index: 0,
+            current: [0, 0],
+            shift: [0, 0],
+            directions,
+        }
+    }
+
+    /// Create a Sobol sequence randomly digitally shifted using `rng`.
+    pub fn scrambled<R: Rng + ?Sized>(rng: &mut R) -> Sobol {
+        Sobol {
+            shift: [rng.gen(), rng.gen()],
+            ..Sobol::new()
+        }
+    }
+}
+
+impl Default for Sobol {
+    fn default() -> Sobol {
+        Sobol::new()
+    }
+}
+
+impl Sampler for Sobol {
+    /// # Panics
+    ///
+    /// Panics once all 2^32 points of the sequence have been used.
+    fn next_point(&mut self) -> (f64, f64) {
+        assert!(
+            self.index < 1 << SOBOL_BITS,
+            "the Sobol sequence only has 2^32 points"
+        );
+
+        // 2^-32, to turn a 32-bit integer into a fraction
+        const SCALE: f64 = 1.0 / (1u64 << SOBOL_BITS) as f64;
+        let x = (self.current[0] ^ self.shift[0]) as f64 * SCALE;
+        let y = (self.current[1] ^ self.shift[1]) as f64 * SCALE;
+
+        // Move on to the next point. In Gray code order this flips the
+        // direction number picked out by the lowest zero bit of the index.
+        let bit = (!self.index).trailing_zeros() as usize;
+        if bit < SOBOL_BITS {
+            self.current[0] ^= self.directions[0][bit];
+            self.current[1] ^= self.directions[1][bit];
+        }
+        self.index += 1;
+
+        (x, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Taking 2^32 points to get to the end would be far too slow, so this
+    // starts the sequence at its last point
+    #[test]
+    #[should_panic(expected = "the Sobol sequence only has 2^32 points")]
+    fn sobol_panics_when_it_runs_out() {
+        let mut sobol = Sobol {
+            index: (1 << SOBOL_BITS) - 1,
+            ..Sobol::new()
+        };
+        sobol.next_point();
+        sobol.next_point();
+    }
+}
@@ -0,0 +1,219 @@
+// Deterministic ways of calculating pi, to set alongside the Monte Carlo
+// estimators. Unlike random sampling, these always give the same answer
+// and can be pushed to as many correct digits as we have patience for.
+
+use crate::bigint::BigUint;
+
+/// Calculate pi from the first `terms` terms of the Leibniz series,
+/// pi = 4 * (1 - 1/3 + 1/5 - 1/7 + ...).
+///
+/// This is the simplest series for pi but also one of the slowest: the
+/// error after n terms is roughly 1/n, so each extra correct digit needs
+/// ten times as many terms.
+pub fn leibniz(terms: u64) -> f64 {
+    let mut sum = 0.0;
+    for k in 0..terms {
+        let term = 1.0 / (2 * k + 1) as f64;
+        if k.is_multiple_of(2) {
+            sum += term;
+        } else {
+            sum -= term;
+        }
+    }
+    4.0 * sum
+}
+
+/// Calculate pi from Machin's formula,
+/// pi = 16 * arctan(1/5) - 4 * arctan(1/239),
+/// using the first `terms` terms of the series for each arctan.
+///
+/// Each term adds about 1.4 correct digits, so around a dozen terms are
+/// enough to use up all of the precision of an f64.
+pub fn machin(terms: u64) -> f64 {
+    16.0 * arctan_inverse(5.0, terms) - 4.0 * arctan_inverse(239.0, terms)
+}
+
+/// arctan(1/x) from the first `terms` terms of its Taylor series,
+/// 1/x - 1/(3 x^3) + 1/(5 x^5) - ...
+fn arctan_inverse(x: f64, terms: u64) -> f64 {
+    let mut sum = 0.0;
+    let mut power = 1.0 / x;
+    for k in 0..terms {
+        let term = power / (2 * k + 1) as f64;
+        if k.is_multiple_of(2) {
+            sum += term;
+        } else {
+            sum -= term;
+        }
+        power /= x * x;
+    }
+    sum
+}
+
+/// The number of extra digits carried through the fixed-point arithmetic
+/// in pi_digits_machin, to soak up rounding errors in the last places.
+const GUARD_DIGITS: usize = 10;
+
+/// The first `k` decimal digits of pi (counting the leading 3), exactly,
+/// using Machin's formula with arbitrary-precision integers.
+///
+/// Every number is stored as an integer multiple of 10^-(k + 10), so the
+/// calculation is exact apart from rounding in the 10 guard digits at the
+/// end, which are thrown away.
+///
+/// # Example
+///
+/// `pi_digits_machin(6)` gives `"314159"`.
+pub fn pi_digits_machin(k: usize) -> String {
+    if k == 0 {
+        return String::new();
+    }
+
+    // 1, in our fixed-point representation
+    let one = BigUint::pow10(k - 1 + GUARD_DIGITS);
+
+    // pi = 4 * (4 * arctan(1/5) - arctan(1/239))
+    let mut pi = arctan_inverse_fixed(5, &one);
+    pi.mul_small(4);
+    pi.sub(&arctan_inverse_fixed(239, &one));
+    pi.mul_small(4);
+
+    let mut digits = pi.to_decimal();
+    digits.truncate(k);
+    digits
+}
+
+/// arctan(1/x) in fixed point, where `one` represents 1, from its Taylor
+/// series 1/x - 1/(3 x^3) + 1/(5 x^5) - ...
+fn arctan_inverse_fixed(x: u32, one: &BigUint) -> BigUint {
+    // power holds one / x^(2n + 1) as we go along
+    let mut power = one.clone();
+    power.div_small(x);
+
+    // Keep the positive and negative terms apart so we never have to
+    // handle a negative number
+    let mut positive = BigUint::from_u32(0);
+    let mut negative = BigUint::from_u32(0);
+
+    let mut n: u32 = 0;
+    while !power.is_zero() {
+        let mut term = power.clone();
+        term.div_small(2 * n + 1);
+        if n.is_multiple_of(2) {
+            positive.add(&term);
+        } else {
+            negative.add(&term);
+        }
+        power.div_small(x * x);
+        n += 1;
+    }
+
+    positive.sub(&negative);
+    positive
+}
+
+/// The first `k` decimal digits of pi (counting the leading 3), exactly,
+/// using the Rabinowitz-Wagon spigot algorithm.
+///
+/// A spigot algorithm produces digits one at a time, left to right, using
+/// only small integers. It works from the series
+/// pi = 2 + 1/3 (2 + 2/5 (2 + 3/7 (2 + ...))), which is a number written
+/// in a "mixed radix" with digits of 2. Converting that into base 10 one
+/// digit at a time gives us the digits of pi.
+///
+/// # Example
+///
+/// `pi_digits_spigot(6)` gives `"314159"`.
+pub fn pi_digits_spigot(k: usize) -> String {
+    // A run of nines can hold back the digits before it until we find out
+    // whether a carry comes along, so work out a few more digits than we
+    // need and drop the extras at the end.
+    let count = k + GUARD_DIGITS;
+
+    // Each mixed-radix digit is worth a little more than a third of a
+    // decimal digit
+    let len = count * 10 / 3 + 1;
+    let mut remainders: Vec<u64> = vec![2; len];
+
+    let mut digits = String::with_capacity(count + 1);
+    let mut predigit: u64 = 0;
+    let mut nines = 0;
+
+    for _ in 0..count {
+        // Multiply by 10 and carry from right to left, leaving behind the
+        // remainder in each position
+        let mut carry: u64 = 0;
+        for i in (1..len).rev() {
+            let value = 10 * remainders[i] + carry * (i as u64 + 1);
+            let base = 2 * i as u64 + 1;
+            remainders[i] = value % base;
+            carry = value / base;
+        }
+        let value = 10 * remainders[0] + carry;
+        remainders[0] = value % 10;
+        let digit = value / 10;
+
+        // A 9 might still turn into a 0 if a later digit carries into it,
+        // so hold nines back until we know. A 10 is exactly that carry.
+        match digit {
+            9 => nines += 1,
+            10 => {
+                digits.push(char::from(b'1' + predigit as u8));
+                digits.extend(std::iter::repeat_n('0', nines));
+                predigit = 0;
+                nines = 0;
+            }
+            _ => {
+                digits.push(char::from(b'0' + predigit as u8));
+                digits.extend(std::iter::repeat_n('9', nines));
+                predigit = digit;
+                nines = 0;
+            }
+        }
+    }
+
+    // The first digit pushed is the placeholder predigit of 0 which comes
+    // before the 3
+    digits.remove(0);
+    digits.truncate(k);
+    digits
+}
+
+/// How many leading decimal digits of `estimate` (counting the leading 3)
+/// agree with the true digits of pi.
+///
+/// This lets us check any of the estimators against pi's real digits,
+/// e.g. `correct_digits(3.1416)` is 4 since 3.141 matches and then 6 does
+/// not match the true next digit 5.
+///
+/// Only estimates from 3 up to (but not including) 4 can have any correct
+/// digits, so anything else - 31.4159 or -3.14159, say - gives 0.
+pub fn correct_digits(estimate: f64) -> usize {
+    // An f64 carries about 17 significant digits, so there's no point
+    // comparing any more than that
+    const MAX_DIGITS: usize = 17;
+
+    // Anything outside this range is wrong in the units, whatever digits
+    // follow. This also rules out NaN and the infinities.
+    if !(3.0..4.0).contains(&estimate) {
+        return 0;
+    }
+    let expected = pi_digits_machin(MAX_DIGITS);
+
+    // Write the estimate out in the shortest form which still reads back
+    // as the same f64, dropping the decimal point so the two strings line
+    // up. Since it's between 3 and 4 the point always comes straight after
+    // the first digit. (Asking for a fixed number of digits would instead
+    // show the binary rounding error, e.g. 3.1416 would come out as
+    // 3.14159999...)
+    let actual: String = format!("{}", estimate)
+        .chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect();
+
+    actual
+        .chars()
+        .zip(expected.chars())
+        .take_while(|(a, b)| a == b)
+        .count()
+}
@@ -0,0 +1,172 @@
+use std::f64::consts::PI;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::Progress;
+
+/// The file formats a convergence trace can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Comma-separated values, with a header line naming the columns
+    Csv,
+    /// One JSON object per line
+    JsonLines,
+}
+
+/// One point on the way to an estimate of pi.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TraceRecord {
+    /// The method which made the estimate, e.g. "slow" or "fast", so that
+    /// the records of several runs in one trace can be told apart
+    pub method: &'static str,
+    /// Which experiment made the estimate, for methods with more than one,
+    /// such as the geometric estimators
+    pub estimator: Option<&'static str>,
+    /// The number of samples taken so far
+    pub samples: u64,
+    /// The estimate for pi after that many samples
+    pub estimate: f64,
+    /// How far the estimate is from the true value of pi
+    pub abs_error: f64,
+    /// The time since the run started
+    pub elapsed: Duration,
+}
+
+impl TraceRecord {
+    /// Make a record, working out the error against
+    /// `std::f64::consts::PI`.
+    pub fn new(
+        method: &'static str,
+        estimator: Option<&'static str>,
+        samples: u64,
+        estimate: f64,
+        elapsed: Duration,
+    ) -> TraceRecord {
+        TraceRecord {
+            method,
+            estimator,
+            samples,
+            estimate,
+            abs_error: (estimate - PI).abs(),
+            elapsed,
+        }
+    }
+}
+
+/// Writes a convergence trace, one record per progress report, so that
+/// runs can be plotted and compared with other tools.
+///
+/// A TraceWriter is a `Progress`, so it can be passed straight to
+/// estimate_pi_with_progress. One trace can hold several runs: call
+/// `start_run` before each one, so that its records are labelled with the
+/// method that made them and timed from the start of that run.
+///
+/// Progress reports have no way to return an error, so if writing fails
+/// the TraceWriter stops writing and holds on to the error until `finish`.
+pub struct TraceWriter<W: Write> {
+    out: W,
+    format: TraceFormat,
+    method: &'static str,
+    estimator: Option<&'static str>,
+    start: Instant,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> TraceWriter<W> {
+    /// Start a trace written to `out` in the given format. For CSV this
+    /// writes the header line straight away.
+    ///
+    /// Until `start_run` is called, records are labelled with the method
+    /// "unknown", and timed from now.
+    pub fn new(mut out: W, format: TraceFormat) -> io::Result<TraceWriter<W>> {
+        if format == TraceFormat::Csv {
+            writeln!(out, "method,estimator,samples,estimate,abs_error,elapsed_seconds")?;
+        }
+
+        Ok(TraceWriter {
+            out,
+            format,
+            method: "unknown",
+            estimator: None,
+            start: Instant::now(),
+            error: None,
+        })
+    }
+
+    /// Label the records which follow as coming from a new run of `method`
+    /// (and `estimator`, if the method has several), and start timing them
+    /// from now.
+    pub fn start_run(&mut self, method: &'static str, estimator: Option<&'static str>) {
+        self.method = method;
+        self.estimator = estimator;
+        self.start = Instant::now();
+    }
+
+    /// The time since the current run was started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Write a single record to the trace.
+    pub fn write_record(&mut self, record: &TraceRecord) -> io::Result<()> {
+        let seconds = record.elapsed.as_secs_f64();
+        match self.format {
+            // Method and estimator names are plain words, which need no
+            // quoting in CSV
+            TraceFormat::Csv => writeln!(
+                self.out,
+                "{},{},{},{},{},{}",
+                record.method,
+                record.estimator.unwrap_or(""),
+                record.samples,
+                record.estimate,
+                record.abs_error,
+                seconds
+            ),
+            TraceFormat::JsonLines => writeln!(
+                self.out,
+                "{{\"method\":\"{}\",\"estimator\":{},\"samples\":{},\"estimate\":{},\"abs_error\":{},\"elapsed_seconds\":{}}}",
+                record.method,
+                record.estimator.map_or("null".to_string(), |name| format!("\"{}\"", name)),
+                record.samples,
+                json_number(record.estimate),
+                json_number(record.abs_error),
+                seconds
+            ),
+        }
+    }
+
+    /// Flush the trace and hand back the writer, or the first error which
+    /// happened while writing the trace.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// A number as JSON. JSON has no NaN or infinity, which an estimate from
+/// no samples can be, so those are written as null.
+///
+/// The estimate_pi program uses this for its JSON output too.
+pub fn json_number(x: f64) -> String {
+    if x.is_finite() {
+        x.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+impl<W: Write> Progress for TraceWriter<W> {
+    fn report(&mut self, samples: u64, pi: f64) {
+        if self.error.is_some() {
+            return;
+        }
+        let record = TraceRecord::new(self.method, self.estimator, samples, pi, self.elapsed());
+        if let Err(error) = self.write_record(&record) {
+            self.error = Some(error);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use rand::Rng;
+
+use crate::estimate_pi_fast::count_hits;
+use crate::{math, Error, Estimate};
+
+/// Ways of getting a more accurate estimate of pi from the same number of
+/// samples, by choosing or combining the samples more cleverly than
+/// estimate_pi_fast does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VarianceReduction {
+    /// Plain Monte Carlo sampling, exactly as estimate_pi_fast does it
+    None,
+    /// Use every random point (x, y) twice: once as it is, and once
+    /// reflected to (1 - x, 1 - y). A point near the centre of the
+    /// quarter-circle is paired with one near its edge, so the errors of
+    /// each pair tend to cancel out.
+    Antithetic,
+    /// Split the unit square into a grid of equal cells and take the same
+    /// number of random points from each one, so no part of the square
+    /// ends up over- or under-represented.
+    Stratified,
+    /// Correct the estimate using x^2 + y^2, which is closely related to
+    /// whether a point is inside the quarter-circle and whose average we
+    /// know exactly (2/3). Whenever the samples happen to have too large
+    /// an average x^2 + y^2 they will also have too few hits, so we can
+    /// adjust for it.
+    ControlVariate,
+}
+
+impl VarianceReduction {
+    /// Every strategy, for comparing them against each other.
+    pub const ALL: [VarianceReduction; 4] = [
+        VarianceReduction::None,
+        VarianceReduction::Antithetic,
+        VarianceReduction::Stratified,
+        VarianceReduction::ControlVariate,
+    ];
+
+    /// A short lowercase name for the strategy.
+    pub fn name(&self) -> &'static str {
+        match self {
+            VarianceReduction::None => "none",
+            VarianceReduction::Antithetic => "antithetic",
+            VarianceReduction::Stratified => "stratified",
+            VarianceReduction::ControlVariate => "control-variate",
+        }
+    }
+
+    /// The fewest points the strategy can measure the variance of its
+    /// estimate from. Antithetic sampling needs two pairs of points, and a
+    /// control variate uses up one point working out how much of the
+    /// control to subtract, on top of the two that any variance needs.
+    pub fn min_samples(&self) -> u64 {
+        match self {
+            VarianceReduction::None => 2,
+            VarianceReduction::Antithetic => 4,
+            VarianceReduction::Stratified => 2,
+            VarianceReduction::ControlVariate => 3,
+        }
+    }
+}
+
+/// The result of estimate_pi_reduced.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReducedEstimate {
+    /// The estimate for pi
+    pub pi: f64,
+    /// The variance of the estimate, measured from the samples themselves
+    pub variance: f64,
+    /// The number of points actually tested. This can be slightly fewer
+    /// than requested, e.g. antithetic sampling needs an even number.
+    pub samples: u64,
+}
+
+impl ReducedEstimate {
+    /// The standard error of the estimate, the square root of its variance.
+    pub fn standard_error(&self) -> f64 {
+        math::sqrt(self.variance)
+    }
+}
+
+/// Estimate pi from (up to) N points using a variance reduction strategy.
+///
+/// Each strategy also measures the variance of its estimate from the
+/// samples it took, so running several strategies with the same N shows
+/// which gives the tightest estimate.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of points to test
+/// * method: the variance reduction strategy to use
+/// * rng: the random number generator to take samples from
+///
+/// # Panics
+///
+/// Panics if N is less than `method.min_samples()`, as there would be no
+/// way to tell how good the estimate is. `try_estimate_pi_reduced` returns
+/// an error instead.
+#[allow(non_snake_case)]
+pub fn estimate_pi_reduced<R: Rng + ?Sized>(
+    N: u64,
+    method: VarianceReduction,
+    rng: &mut R,
+) -> ReducedEstimate {
+    assert!(
+        N >= method.min_samples(),
+        "N must be at least {} for VarianceReduction::{:?}",
+        method.min_samples(),
+        method
+    );
+
+    match method {
+        VarianceReduction::None => plain(N, rng),
+        VarianceReduction::Antithetic => antithetic(N, rng),
+        VarianceReduction::Stratified => stratified(N, rng),
+        VarianceReduction::ControlVariate => control_variate(N, rng),
+    }
+}
+
+/// Version of estimate_pi_reduced which checks N first, returning an
+/// error instead of panicking.
+///
+/// # Arguments
+///
+/// * N: a 64-bit positive integer, the number of points to test
+/// * method: the variance reduction strategy to use
+/// * rng: the random number generator to take samples from
+///
+/// # Errors
+///
+/// Returns `Error::ZeroSamples` if N is zero, or `Error::TooFewSamples` if
+/// it is less than `method.min_samples()`.
+#[allow(non_snake_case)]
+pub fn try_estimate_pi_reduced<R: Rng + ?Sized>(
+    N: u64,
+    method: VarianceReduction,
+    rng: &mut R,
+) -> Result<ReducedEstimate, Error> {
+    if N == 0 {
+        return Err(Error::ZeroSamples);
+    }
+    if N < method.min_samples() {
+        return Err(Error::TooFewSamples);
+    }
+    Ok(estimate_pi_reduced(N, method, rng))
+}
+
+#[allow(non_snake_case)]
+fn plain<R: Rng + ?Sized>(N: u64, rng: &mut R) -> ReducedEstimate {
+    let estimate = Estimate::new(count_hits(N, rng), N);
+    ReducedEstimate {
+        pi: estimate.pi(),
+        variance: estimate.standard_error() * estimate.standard_error(),
+        samples: N,
+    }
+}
+
+/// 4 if the point is inside the quarter-circle, otherwise 0. The average
+/// of this over many points is our estimate for pi.
+fn score(x: f64, y: f64) -> f64 {
+    if (x*x + y*y) < 1.0 {
+        4.0
+    } else {
+        0.0
+    }
+}
+
+#[allow(non_snake_case)]
+fn antithetic<R: Rng + ?Sized>(N: u64, rng: &mut R) -> ReducedEstimate {
+    let pairs = N / 2;
+    let mut moments = Moments::default();
+
+    for _ in 0..pairs {
+        let x: f64 = rng.gen();
+        let y: f64 = rng.gen();
+
+        // The average score of the point and its reflection
+        moments.add((score(x, y) + score(1.0 - x, 1.0 - y)) / 2.0);
+    }
+
+    // The pair averages are independent of each other, so the variance of
+    // their mean is their own variance divided by the number of pairs
+    ReducedEstimate {
+        pi: moments.mean(),
+        variance: moments.variance() / pairs as f64,
+        samples: 2 * pairs,
+    }
+}
+
+#[allow(non_snake_case)]
+fn stratified<R: Rng + ?Sized>(N: u64, rng: &mut R) -> ReducedEstimate {
+    // Use a k by k grid with at least two points per cell, so that we can
+    // measure the variance within each cell
+    let k = (math::sqrt((N / 2) as f64) as u64).max(1);
+    let per_cell = N / (k * k);
+    let cell_size = 1.0 / k as f64;
+
+    let mut pi = 0.0;
+    let mut variance = 0.0;
+
+    for i in 0..k {
+        for j in 0..k {
+            let mut moments = Moments::default();
+            for _ in 0..per_cell {
+                let x = (i as f64 + rng.gen::<f64>()) * cell_size;
+                let y = (j as f64 + rng.gen::<f64>()) * cell_size;
+                moments.add(score(x, y));
+            }
+
+            // Each cell is 1 / k^2 of the square, so it contributes that
+            // fraction of the estimate, and that fraction squared of the
+            // variance
+            let weight = 1.0 / (k * k) as f64;
+            pi += weight * moments.mean();
+            variance += weight * weight * moments.variance() / per_cell as f64;
+        }
+    }
+
+    ReducedEstimate {
+        pi,
+        variance,
+        samples: per_cell * k * k,
+    }
+}
+
+#[allow(non_snake_case)]
+fn control_variate<R: Rng + ?Sized>(N: u64, rng: &mut R) -> ReducedEstimate {
+    // The exact average of x^2 + y^2 over the unit square
+    const CONTROL_MEAN: f64 = 2.0 / 3.0;
+
+    let mut scores = Moments::default();
+    let mut controls = Moments::default();
+    let mut covariance = 0.0;
+
+    for _ in 0..N {
+        let x: f64 = rng.gen();
+        let y: f64 = rng.gen();
+        let f = score(x, y);
+        let g = x*x + y*y;
+
+        // Welford's update for the covariance needs the score's mean from
+        // before this sample and the control's mean from after it
+        let f_delta = f - scores.mean();
+        scores.add(f);
+        controls.add(g);
+        covariance += f_delta * (g - controls.mean());
+    }
+    let covariance = covariance / (N as f64 - 1.0);
+
+    // The best multiple of the control to subtract is cov(f, g) / var(g).
+    // It removes the part of the variance of f which g can explain.
+    let c = covariance / controls.variance();
+    let variance = scores.variance() - c * covariance;
+
+    ReducedEstimate {
+        pi: scores.mean() - c * (controls.mean() - CONTROL_MEAN),
+        variance: variance / N as f64,
+        samples: N,
+    }
+}
+
+/// The running mean and variance of a stream of values, using Welford's
+/// method.
+#[derive(Default)]
+pub(crate) struct Moments {
+    count: u64,
+    mean: f64,
+    squares: f64,
+}
+
+impl Moments {
+    pub(crate) fn add(&mut self, value: f64) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squares += delta * (value - self.mean);
+    }
+
+    pub(crate) fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// The sample variance of the values added so far.
+    pub(crate) fn variance(&self) -> f64 {
+        if self.count > 1 {
+            self.squares / (self.count - 1) as f64
+        } else {
+            0.0
+        }
+    }
+}
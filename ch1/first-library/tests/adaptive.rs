@@ -0,0 +1,47 @@
+//! Checks that estimate_pi_adaptive only stops for its tolerance once it
+//! really has an estimate that good.
+
+use first_library::{estimate_pi_adaptive, seeded_rng, AdaptiveOptions, Error, StopReason};
+
+#[test]
+fn tiny_batches_dont_stop_on_a_lucky_start() {
+    // A standard error of 1e-3 needs millions of samples, so a run of at
+    // most 10000 can never honestly reach it. The first sample always hits
+    // or misses, which would make the plain standard error 0.
+    let options = AdaptiveOptions {
+        tolerance: 1e-3,
+        max_samples: 10_000,
+        time_budget: None,
+        batch_size: 1,
+    };
+    for seed in 0..200 {
+        let result = estimate_pi_adaptive(&options, &mut seeded_rng(seed));
+        assert_eq!(result.stopped_by, StopReason::MaxSamples, "seed {}", seed);
+        assert_eq!(result.estimate.samples, 10_000);
+    }
+}
+
+#[test]
+fn loose_tolerance_is_met() {
+    let options = AdaptiveOptions {
+        tolerance: 1e-2,
+        batch_size: 1000,
+        ..AdaptiveOptions::default()
+    };
+    let result = estimate_pi_adaptive(&options, &mut seeded_rng(1));
+    assert_eq!(result.stopped_by, StopReason::Tolerance);
+    assert!(result.estimate.standard_error() < 1e-2);
+    assert!((result.estimate.pi() - std::f64::consts::PI).abs() < 0.05);
+}
+
+#[test]
+fn tolerance_must_be_positive() {
+    for tolerance in [0.0, -1e-3, f64::NAN] {
+        let options = AdaptiveOptions {
+            tolerance,
+            ..AdaptiveOptions::default()
+        };
+        assert_eq!(options.validate(), Err(Error::InvalidTolerance));
+    }
+    assert_eq!(AdaptiveOptions::default().validate(), Ok(()));
+}
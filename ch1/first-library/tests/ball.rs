@@ -0,0 +1,50 @@
+//! The exact ball volumes come from a recurrence rather than the closed
+//! form, so check them against the closed form for the first few
+//! dimensions, and check that the estimates find pi where they can.
+
+use std::f64::consts::PI;
+
+use first_library::{estimate_ball_volume, seeded_rng, unit_ball_volume};
+
+#[test]
+fn exact_volumes_match_the_closed_forms() {
+    let expected = [
+        1.0,
+        2.0,
+        PI,
+        4.0 * PI / 3.0,
+        PI * PI / 2.0,
+        8.0 * PI * PI / 15.0,
+        PI * PI * PI / 6.0,
+    ];
+    for (dimensions, &volume) in expected.iter().enumerate() {
+        let exact = unit_ball_volume(dimensions as u32);
+        assert!(
+            (exact - volume).abs() < 1e-12,
+            "V({}) = {}, expected {}",
+            dimensions,
+            exact,
+            volume
+        );
+    }
+}
+
+#[test]
+fn pi_is_nan_below_two_dimensions() {
+    for dimensions in 0..2 {
+        let ball = estimate_ball_volume(dimensions, 1000, &mut seeded_rng(1));
+        assert!(ball.pi().is_nan(), "{:?}", ball);
+    }
+}
+
+#[test]
+fn three_dimensions_give_pi() {
+    let ball = estimate_ball_volume(3, 200_000, &mut seeded_rng(1));
+    assert!(ball.pi_standard_error() > 0.0, "{:?}", ball);
+    assert!(
+        (ball.pi() - PI).abs() < 4.0 * ball.pi_standard_error(),
+        "{} is too far from pi: {:?}",
+        ball.pi(),
+        ball
+    );
+}
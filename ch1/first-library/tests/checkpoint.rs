@@ -0,0 +1,66 @@
+//! Checkpoints must survive being written out and read back, and a run
+//! which is stopped and resumed must end exactly where an uninterrupted
+//! one would.
+
+use std::fs;
+use std::io::ErrorKind;
+
+use first_library::{estimate_pi_checkpointed, estimate_pi_deterministic, Checkpoint, NoProgress};
+
+#[test]
+fn checkpoint_survives_a_round_trip() {
+    let mut checkpoint = Checkpoint::new(1_000_000, 2024);
+    checkpoint.run(123_457);
+
+    let mut text = Vec::new();
+    checkpoint.write_to(&mut text).unwrap();
+    let read = Checkpoint::read_from(&text[..]).unwrap();
+    assert_eq!(read, checkpoint);
+
+    // Carrying on from either gives the same samples
+    let mut original = checkpoint;
+    let mut read = read;
+    original.run(1_000);
+    read.run(1_000);
+    assert_eq!(read, original);
+}
+
+#[test]
+fn invalid_checkpoints_are_rejected() {
+    let mut text = Vec::new();
+    Checkpoint::new(1_000, 1).write_to(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+
+    let bad = [
+        String::new(),
+        text.replacen("# first-library", "# other", 1),
+        text.replacen("hits=0", "hits=5", 1),
+        text.replacen("seed=1\n", "", 1),
+        text.replacen("samples=1000", "samples=lots", 1),
+        text + "colour=blue\n",
+    ];
+    for input in bad.iter() {
+        let error = Checkpoint::read_from(input.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData, "accepted {:?}", input);
+    }
+}
+
+#[test]
+fn resumed_run_matches_the_deterministic_estimate() {
+    let path = std::env::temp_dir().join(format!("first-library-test-{}.checkpoint", std::process::id()));
+
+    // Stop part of the way through, between two checkpoints
+    let mut checkpoint = Checkpoint::new(1_000_000, 2024);
+    checkpoint.run(123_457);
+    checkpoint.save(&path).unwrap();
+
+    let resumed = Checkpoint::load(&path).unwrap();
+    let estimate = estimate_pi_checkpointed(resumed, &path, 100_000, &mut NoProgress).unwrap();
+    assert_eq!(estimate, estimate_pi_deterministic(1_000_000, 2024));
+
+    // The last checkpoint saved is the finished run
+    let finished = Checkpoint::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(finished.is_done());
+    assert_eq!(finished.estimate(), estimate);
+}
@@ -0,0 +1,44 @@
+//! The deterministic estimator must give exactly these hit counts on every
+//! platform. If one of these tests fails on some target, results from that
+//! target can't be compared with anywhere else.
+
+use first_library::{estimate_pi_deterministic, estimate_pi_fast_kernel, Kernel, Pcg32};
+use rand::RngCore;
+
+#[test]
+fn pcg32_matches_the_reference_implementation() {
+    // The first outputs of pcg32-demo from the PCG reference C library
+    let mut rng = Pcg32::new(42, 54);
+    let expected = [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e];
+    for &value in expected.iter() {
+        assert_eq!(rng.next_u32(), value);
+    }
+}
+
+#[test]
+fn known_hit_counts() {
+    let cases = [
+        // (samples, seed, hits)
+        (1, 0, 1),
+        (10, 1, 9),
+        (1_000, 42, 784),
+        (1_000_000, 7, 784_777),
+        (1_000_000, 2024, 785_420),
+    ];
+    for &(samples, seed, hits) in cases.iter() {
+        let estimate = estimate_pi_deterministic(samples, seed);
+        assert_eq!(estimate.samples, samples);
+        assert_eq!(estimate.hits, hits, "{} samples with seed {}", samples, seed);
+    }
+}
+
+#[test]
+fn known_estimate() {
+    assert_eq!(estimate_pi_deterministic(1_000_000, 2024).pi(), 3.14168);
+}
+
+#[test]
+fn kernel_gives_the_same_counts() {
+    let estimate = estimate_pi_fast_kernel(1_000_000, Kernel::Deterministic, 2024);
+    assert_eq!(estimate, estimate_pi_deterministic(1_000_000, 2024));
+}
@@ -0,0 +1,71 @@
+//! Runs a coordinator against workers on the other end of local sockets,
+//! including ones which fail, to check that every job is done exactly once
+//! and the result doesn't depend on which worker did what.
+#![cfg(unix)]
+
+use std::io::{self, BufReader};
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+use first_library::{run_worker, CancelToken, Coordinator, Estimate, Job, Message};
+
+/// Serve jobs to a healthy worker running on another thread.
+fn serve_worker(coordinator: &Coordinator) -> io::Result<()> {
+    let (ours, theirs) = UnixStream::pair()?;
+    let worker = thread::spawn(move || run_worker(BufReader::new(theirs.try_clone()?), theirs));
+    coordinator.serve(BufReader::new(ours.try_clone()?), ours, &CancelToken::new())?;
+    worker.join().expect("worker thread panicked")
+}
+
+#[test]
+fn messages_survive_a_round_trip() {
+    let messages = [
+        Message::Run(Job {
+            id: 3,
+            seed: u64::MAX,
+            samples: 1_000_000,
+        }),
+        Message::Hits {
+            job: 3,
+            estimate: Estimate::new(785_420, 1_000_000),
+        },
+        Message::Quit,
+    ];
+    for message in messages {
+        assert_eq!(Message::parse(&message.to_string()).unwrap(), message);
+    }
+
+    for bad in ["", "RUN 1 2", "RUN 1 2 x", "HITS 0 5 4", "STOP"] {
+        assert!(Message::parse(bad).is_err(), "accepted '{}'", bad);
+    }
+}
+
+#[test]
+fn failed_workers_dont_change_the_result() {
+    let expected = Coordinator::new(1_000_000, 150_000, 11);
+    serve_worker(&expected).unwrap();
+    assert!(expected.is_done());
+    assert_eq!(expected.estimate().samples, 1_000_000);
+
+    let coordinator = Coordinator::new(1_000_000, 150_000, 11);
+    let cancel = CancelToken::new();
+    // One worker which dies straight away, and one which replies about
+    // the wrong job. Each takes a job and fails it.
+    assert!(coordinator.serve(io::empty(), io::sink(), &cancel).is_err());
+    assert!(coordinator.serve(&b"HITS 99 1 1\n"[..], io::sink(), &cancel).is_err());
+    assert!(!coordinator.is_done());
+
+    serve_worker(&coordinator).unwrap();
+    assert!(coordinator.is_done());
+    assert_eq!(coordinator.estimate(), expected.estimate());
+}
+
+#[test]
+fn cancelled_coordinator_hands_out_no_more_jobs() {
+    let coordinator = Coordinator::new(1_000_000, 100_000, 11);
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    coordinator.serve(io::empty(), io::sink(), &cancel).unwrap();
+    assert_eq!(coordinator.estimate().samples, 0);
+    assert!(!coordinator.is_done());
+}
@@ -0,0 +1,57 @@
+//! Invalid settings are turned away with the right Error, whichever way
+//! they come in.
+
+use first_library::{
+    estimate_pi, estimate_pi_detailed, estimate_pi_seeded, seeded_rng, try_estimate_pi,
+    try_estimate_pi_parallel, Config, Error, NoProgress,
+};
+
+#[test]
+fn config_rejects_invalid_settings() {
+    assert_eq!(Config::builder().build(), Err(Error::ZeroSamples));
+    assert_eq!(Config::builder().samples(0).build(), Err(Error::ZeroSamples));
+    assert_eq!(
+        Config::builder().samples(100).display(0).build(),
+        Err(Error::ZeroDisplay)
+    );
+    assert_eq!(
+        Config::builder().samples(100).threads(0).build(),
+        Err(Error::ZeroThreads)
+    );
+    assert!(Config::builder().samples(100).build().is_ok());
+}
+
+#[test]
+fn try_functions_return_errors() {
+    assert_eq!(try_estimate_pi(0, 10), Err(Error::ZeroSamples));
+    assert_eq!(try_estimate_pi(100, 0), Err(Error::ZeroDisplay));
+    assert_eq!(try_estimate_pi_parallel(0, 2, 1), Err(Error::ZeroSamples));
+    assert_eq!(try_estimate_pi_parallel(100, 0, 1), Err(Error::ZeroThreads));
+}
+
+#[test]
+#[should_panic(expected = "display must be positive")]
+fn estimate_pi_panics_without_a_display_interval() {
+    estimate_pi(100, 0);
+}
+
+// The rest of the estimate_pi family is just as strict, rather than
+// quietly never reporting
+#[test]
+#[should_panic(expected = "display must be positive")]
+fn estimate_pi_seeded_panics_without_a_display_interval() {
+    estimate_pi_seeded(100, 0, 1);
+}
+
+#[test]
+#[should_panic(expected = "display must be positive")]
+fn estimate_pi_detailed_panics_without_a_display_interval() {
+    estimate_pi_detailed(100, 0, &mut seeded_rng(1), &mut NoProgress);
+}
+
+// No samples give no estimate, which estimate_pi has always returned as 0
+#[test]
+fn no_samples_give_zero() {
+    assert_eq!(estimate_pi(0, 10), 0.0);
+    assert_eq!(estimate_pi_seeded(0, 10, 1), 0.0);
+}
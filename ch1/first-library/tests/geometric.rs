@@ -0,0 +1,55 @@
+//! Each geometric experiment turns its average outcome into pi with its
+//! own formula, so check every one of them lands near pi.
+
+use std::f64::consts::PI;
+
+use first_library::{
+    estimate_pi_geometric, geometric_estimators, seeded_rng, BuffonLaplace, BuffonNeedle,
+    GeometricEstimator,
+};
+
+/// Check that `estimator` gives an estimate within four standard errors
+/// of pi, which a correct formula fails about once in 16,000 seeds.
+fn check_close_to_pi(estimator: &dyn GeometricEstimator) {
+    let result = estimate_pi_geometric(estimator, 200_000, &mut seeded_rng(1));
+    assert_eq!(result.trials, 200_000);
+    assert!(
+        result.standard_error > 0.0 && result.standard_error < 0.05,
+        "{}: {:?}",
+        estimator.name(),
+        result
+    );
+    assert!(
+        (result.pi - PI).abs() < 4.0 * result.standard_error,
+        "{}: {:?}",
+        estimator.name(),
+        result
+    );
+}
+
+#[test]
+fn every_estimator_is_close_to_pi() {
+    let estimators = geometric_estimators();
+    assert_eq!(estimators.len(), 5);
+    for estimator in estimators {
+        check_close_to_pi(&*estimator);
+    }
+}
+
+#[test]
+fn needles_shorter_than_the_gap_are_close_to_pi() {
+    check_close_to_pi(&BuffonNeedle::new(0.5, 1.0));
+    check_close_to_pi(&BuffonLaplace::new(0.5, 1.0, 2.0));
+}
+
+#[test]
+#[should_panic(expected = "the needle must be no longer than the gap between the lines")]
+fn buffon_needle_longer_than_the_gap_panics() {
+    BuffonNeedle::new(2.0, 1.0);
+}
+
+#[test]
+#[should_panic(expected = "the needle must be no longer than the sides of the rectangles")]
+fn buffon_laplace_needle_longer_than_a_side_panics() {
+    BuffonLaplace::new(1.5, 2.0, 1.0);
+}
@@ -0,0 +1,79 @@
+//! Checks the integration API against integrals and volumes we know, and
+//! that building estimate_pi on it didn't change its results.
+
+use std::f64::consts::PI;
+
+use first_library::{
+    estimate_pi_detailed, estimate_pi_seeded, integrate, seeded_rng, volume, Estimate, Progress,
+};
+use rand::Rng;
+
+#[test]
+fn integral_of_x_squared() {
+    // The integral of x^2 from 0 to 3 is 3^3 / 3 = 9
+    let integral = integrate(|x| x[0] * x[0], &[(0.0, 3.0)], 100_000, &mut seeded_rng(1));
+    assert_eq!(integral.samples, 100_000);
+    assert!(
+        (integral.value - 9.0).abs() < integral.error_bound(0.99),
+        "{:?}",
+        integral
+    );
+}
+
+#[test]
+fn constant_integral_is_the_box_volume() {
+    // A 2 by 2.5 box, which doesn't start at the origin
+    let integral = integrate(|_| 1.0, &[(1.0, 3.0), (-2.0, 0.5)], 1_000, &mut seeded_rng(1));
+    assert_eq!(integral.value, 5.0);
+    assert_eq!(integral.standard_error, 0.0);
+}
+
+#[test]
+fn volume_of_a_disc_in_a_larger_box() {
+    // A disc of radius 2 fills pi * 4 of the 4 by 4 box around it
+    let disc = volume(
+        |p| p[0] * p[0] + p[1] * p[1] < 4.0,
+        &[(-2.0, 2.0), (-2.0, 2.0)],
+        100_000,
+        &mut seeded_rng(2),
+    );
+    assert_eq!(disc.box_volume, 16.0);
+    assert_eq!(disc.samples, 100_000);
+    assert!((disc.value() - 4.0 * PI).abs() < disc.error_bound(0.99), "{:?}", disc);
+}
+
+/// Keeps every progress report.
+#[derive(Default)]
+struct Reports(Vec<(u64, f64)>);
+
+impl Progress for Reports {
+    fn report(&mut self, samples: u64, pi: f64) {
+        self.0.push((samples, pi));
+    }
+}
+
+#[test]
+fn estimate_pi_is_unchanged_by_the_integration_api() {
+    // The loop estimate_pi had before it was built on volume: x and then y
+    // from the generator, and a report every `display` samples
+    let (samples, display, seed) = (100_000, 7_000, 42);
+    let mut rng = seeded_rng(seed);
+    let mut expected_reports = Reports::default();
+    let mut hits = 0;
+    for n in 1..=samples {
+        let x: f64 = rng.gen();
+        let y: f64 = rng.gen();
+        if x * x + y * y < 1.0 {
+            hits += 1;
+        }
+        if n % display == 0 {
+            expected_reports.report(n, 4.0 * (hits as f64 / n as f64));
+        }
+    }
+
+    let mut reports = Reports::default();
+    let estimate = estimate_pi_detailed(samples, display, &mut seeded_rng(seed), &mut reports);
+    assert_eq!(estimate, Estimate::new(hits, samples));
+    assert_eq!(reports.0, expected_reports.0);
+    assert_eq!(estimate_pi_seeded(samples, samples, seed), estimate.pi());
+}
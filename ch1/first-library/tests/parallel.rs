@@ -0,0 +1,16 @@
+//! A parallel estimate must depend only on its seed and number of threads,
+//! not on the order the threads happen to finish in.
+
+use first_library::estimate_pi_parallel;
+
+#[test]
+fn parallel_estimates_depend_only_on_the_seed_and_threads() {
+    let first = estimate_pi_parallel(1_000_000, 4, 42);
+    assert_eq!(first, 3.142788);
+    for _ in 0..3 {
+        assert_eq!(estimate_pi_parallel(1_000_000, 4, 42), first);
+    }
+
+    // Splitting the samples differently gives each thread different ones
+    assert_eq!(estimate_pi_parallel(1_000_000, 3, 42), 3.140864);
+}
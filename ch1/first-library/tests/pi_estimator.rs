@@ -0,0 +1,52 @@
+//! A PiEstimator keeps its counts between calls, so check that adding
+//! and merging samples keeps them straight.
+
+use first_library::{Estimate, PiEstimator};
+
+#[test]
+fn merged_halves_add_up_their_counts() {
+    let mut first = PiEstimator::seeded(1);
+    first.add_samples(50_000);
+    let mut second = PiEstimator::seeded(2);
+    second.add_samples(30_000);
+
+    let mut merged = PiEstimator::seeded(3);
+    merged.merge(&first);
+    merged.merge(&second);
+
+    let expected = Estimate::new(
+        first.estimate().hits + second.estimate().hits,
+        first.estimate().samples + second.estimate().samples,
+    );
+    assert_eq!(merged.estimate(), expected);
+    assert_eq!(merged.samples(), 80_000);
+    assert_eq!(merged.pi(), expected.pi());
+}
+
+#[test]
+fn merge_estimate_adds_the_counts() {
+    let mut estimator = PiEstimator::seeded(1);
+    estimator.merge_estimate(Estimate::new(3, 4));
+    estimator.merge_estimate(Estimate::new(7, 8));
+    assert_eq!(estimator.estimate(), Estimate::new(10, 12));
+}
+
+#[test]
+fn the_estimate_does_not_depend_on_how_the_samples_are_split() {
+    let mut whole = PiEstimator::seeded(42);
+    whole.add_samples(100_000);
+
+    let mut pieces = PiEstimator::seeded(42);
+    for n in [1, 999, 40_000, 0, 59_000] {
+        pieces.add_samples(n);
+    }
+
+    assert_eq!(pieces.estimate(), whole.estimate());
+}
+
+#[test]
+fn no_samples_means_no_estimate() {
+    let estimator = PiEstimator::seeded(1);
+    assert_eq!(estimator.samples(), 0);
+    assert!(estimator.pi().is_nan());
+}
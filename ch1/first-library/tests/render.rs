@@ -0,0 +1,43 @@
+//! Checks the structure of rendered pictures, rather than every pixel.
+
+use first_library::{render_samples, Halton, ImageFormat, RenderOptions};
+
+fn render(num_points: u64, options: &RenderOptions) -> Vec<u8> {
+    let mut out = Vec::new();
+    render_samples(num_points, &mut Halton::new(), options, &mut out).unwrap();
+    out
+}
+
+#[test]
+fn svg_has_an_element_per_point() {
+    let svg = String::from_utf8(render(100, &RenderOptions::default())).unwrap();
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.ends_with("</svg>\n"));
+    assert_eq!(svg.matches("<rect ").count(), 1);
+    assert_eq!(svg.matches("<circle ").count(), 100);
+    // 90 segments for the quarter-circle and 4 for the square
+    assert_eq!(svg.matches("<line ").count(), 94);
+}
+
+#[test]
+fn ppm_has_a_header_and_every_pixel() {
+    let options = RenderOptions {
+        size: 50,
+        convergence: false,
+        format: ImageFormat::Ppm,
+    };
+    let header = b"P6\n50 50\n255\n";
+    let ppm = render(1_000, &options);
+    assert!(ppm.starts_with(header));
+    assert_eq!(ppm.len(), header.len() + 3 * 50 * 50);
+
+    // The convergence plot goes alongside, doubling the width
+    let options = RenderOptions {
+        convergence: true,
+        ..options
+    };
+    let header = b"P6\n100 50\n255\n";
+    let ppm = render(1_000, &options);
+    assert!(ppm.starts_with(header));
+    assert_eq!(ppm.len(), header.len() + 3 * 100 * 50);
+}
@@ -0,0 +1,76 @@
+//! The low-discrepancy sequences are worked out by hand, so pin their
+//! first points against the published ones and check the properties the
+//! estimators rely on.
+
+use first_library::{seeded_rng, Halton, Sampler, Sobol};
+
+fn first_points<S: Sampler>(sampler: &mut S, n: usize) -> Vec<(f64, f64)> {
+    (0..n).map(|_| sampler.next_point()).collect()
+}
+
+#[test]
+fn halton_starts_with_the_van_der_corput_sequences() {
+    // Bases 2 and 3: 0, 1/2, 1/4, 3/4, 1/8 and 0, 1/3, 2/3, 1/9, 4/9
+    let expected = [
+        (0.0, 0.0),
+        (0.5, 1.0 / 3.0),
+        (0.25, 2.0 / 3.0),
+        (0.75, 1.0 / 9.0),
+        (0.125, 4.0 / 9.0),
+    ];
+    for (point, expected) in first_points(&mut Halton::new(), 5).into_iter().zip(expected) {
+        assert!((point.0 - expected.0).abs() < 1e-15, "{:?} != {:?}", point, expected);
+        assert!((point.1 - expected.1).abs() < 1e-15, "{:?} != {:?}", point, expected);
+    }
+}
+
+#[test]
+fn sobol_starts_with_the_known_points() {
+    // These are all exact binary fractions, so can be compared exactly
+    let expected = vec![
+        (0.0, 0.0),
+        (0.5, 0.5),
+        (0.75, 0.25),
+        (0.25, 0.75),
+        (0.375, 0.375),
+        (0.875, 0.875),
+    ];
+    assert_eq!(first_points(&mut Sobol::new(), 6), expected);
+}
+
+#[test]
+fn sobol_puts_one_point_in_each_strip() {
+    // The first 2^k points have exactly one in each of the 2^k strips of
+    // width 2^-k, across and down
+    for k in 1..=10 {
+        let n = 1 << k;
+        let points = first_points(&mut Sobol::new(), n);
+        for coordinate in [|p: &(f64, f64)| p.0, |p: &(f64, f64)| p.1] {
+            let mut strips: Vec<usize> =
+                points.iter().map(|p| (coordinate(p) * n as f64) as usize).collect();
+            strips.sort_unstable();
+            assert_eq!(strips, (0..n).collect::<Vec<_>>(), "first {} points", n);
+        }
+    }
+}
+
+#[test]
+fn scrambled_points_stay_in_the_unit_square() {
+    for seed in 0..20 {
+        let mut rng = seeded_rng(seed);
+        let mut halton = Halton::scrambled(&mut rng);
+        let mut sobol = Sobol::scrambled(&mut rng);
+        let mut points = first_points(&mut halton, 1_000);
+        points.extend(first_points(&mut sobol, 1_000));
+        for (x, y) in points {
+            assert!((0.0..1.0).contains(&x) && (0.0..1.0).contains(&y), "({}, {})", x, y);
+        }
+    }
+}
+
+#[test]
+fn scrambling_changes_the_points() {
+    let plain = first_points(&mut Sobol::new(), 10);
+    let scrambled = first_points(&mut Sobol::scrambled(&mut seeded_rng(1)), 10);
+    assert_ne!(plain, scrambled);
+}
@@ -0,0 +1,18 @@
+//! Seeded estimates must come out exactly the same every time, so a change
+//! to how the samples are drawn or counted shows up here. The values only
+//! hold for this version of rand, whose StdRng may change between versions.
+
+use first_library::{estimate_pi_fast_seeded, estimate_pi_seeded};
+
+#[test]
+fn known_seeded_estimates() {
+    assert_eq!(estimate_pi_seeded(1_000, 1_000, 7), 3.212);
+    assert_eq!(estimate_pi_seeded(100_000, 100_000, 42), 3.14164);
+}
+
+#[test]
+fn known_fast_seeded_estimates() {
+    assert_eq!(estimate_pi_fast_seeded(100_000, 42), 3.14164);
+    assert_eq!(estimate_pi_fast_seeded(1_000_000, 7), 3.142944);
+}
+
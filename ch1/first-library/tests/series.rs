@@ -0,0 +1,52 @@
+//! Checks the exact digit calculations against the published digits of pi.
+
+// Rough values of pi are the whole point here
+#![allow(clippy::approx_constant)]
+
+use first_library::{correct_digits, pi_digits_machin, pi_digits_spigot};
+
+/// The first 50 digits of pi, counting the leading 3
+const FIRST_DIGITS: &str = "31415926535897932384626433832795028841971693993751";
+
+/// Digits 750 to 779, taking the leading 3 as digit 0. The run of six nines
+/// (the Feynman point) starts at digit 762, and a spigot has to hold all of
+/// them back until it finds out whether a carry comes along.
+const AROUND_THE_NINES: &str = "051870721134999999837297804995";
+
+#[test]
+fn machin_matches_the_known_digits() {
+    assert_eq!(pi_digits_machin(50), FIRST_DIGITS);
+    assert_eq!(&pi_digits_machin(780)[750..], AROUND_THE_NINES);
+}
+
+#[test]
+fn spigot_matches_the_known_digits() {
+    assert_eq!(pi_digits_spigot(50), FIRST_DIGITS);
+    assert_eq!(&pi_digits_spigot(780)[750..], AROUND_THE_NINES);
+}
+
+#[test]
+fn digits_stop_in_the_right_place() {
+    // Ending in the middle of the nines, or just before or after them,
+    // mustn't drop or invent any digits
+    for k in [762, 763, 767, 768, 769] {
+        assert_eq!(pi_digits_machin(k).len(), k);
+        assert_eq!(pi_digits_spigot(k), pi_digits_machin(k), "{} digits", k);
+    }
+}
+
+#[test]
+fn no_digits() {
+    assert_eq!(pi_digits_machin(0), "");
+    assert_eq!(pi_digits_spigot(0), "");
+}
+
+#[test]
+fn correct_digits_checks_the_magnitude() {
+    assert_eq!(correct_digits(3.1416), 4);
+    assert_eq!(correct_digits(3.14159), 6);
+    assert_eq!(correct_digits(std::f64::consts::PI), 16);
+    for wrong in [31.4159, -3.14159, 0.314159, 4.0, f64::NAN, f64::INFINITY] {
+        assert_eq!(correct_digits(wrong), 0, "{}", wrong);
+    }
+}
@@ -0,0 +1,51 @@
+//! Traces hold several runs, so every record has to say which run it came
+//! from.
+
+use std::time::Duration;
+
+use first_library::{Progress, TraceFormat, TraceRecord, TraceWriter};
+
+#[test]
+fn csv_records_are_labelled() {
+    let mut trace = TraceWriter::new(Vec::new(), TraceFormat::Csv).unwrap();
+    trace.start_run("slow", None);
+    trace.report(100, 3.0);
+    trace.start_run("geometric", Some("buffon"));
+    trace.report(100, 3.5);
+    let text = String::from_utf8(trace.finish().unwrap()).unwrap();
+
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "method,estimator,samples,estimate,abs_error,elapsed_seconds");
+    assert!(lines[1].starts_with("slow,,100,3,"), "{}", lines[1]);
+    assert!(lines[2].starts_with("geometric,buffon,100,3.5,"), "{}", lines[2]);
+    assert_eq!(lines.len(), 3);
+}
+
+#[test]
+fn json_lines_records_are_labelled() {
+    let mut trace = TraceWriter::new(Vec::new(), TraceFormat::JsonLines).unwrap();
+    trace
+        .write_record(&TraceRecord::new("fast", None, 10, 4.0, Duration::from_secs(2)))
+        .unwrap();
+    trace
+        .write_record(&TraceRecord::new("geometric", Some("dart"), 0, f64::NAN, Duration::ZERO))
+        .unwrap();
+    let text = String::from_utf8(trace.finish().unwrap()).unwrap();
+
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("{\"method\":\"fast\",\"estimator\":null,\"samples\":10,\"estimate\":4,"));
+    assert!(lines[0].ends_with(",\"elapsed_seconds\":2}"));
+    assert_eq!(
+        lines[1],
+        "{\"method\":\"geometric\",\"estimator\":\"dart\",\"samples\":0,\"estimate\":null,\"abs_error\":null,\"elapsed_seconds\":0}"
+    );
+}
+
+#[test]
+fn each_run_is_timed_from_its_own_start() {
+    let mut trace = TraceWriter::new(Vec::new(), TraceFormat::Csv).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    trace.start_run("fast", None);
+    assert!(trace.elapsed() < Duration::from_millis(200));
+}
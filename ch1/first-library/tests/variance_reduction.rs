@@ -0,0 +1,43 @@
+//! Every variance reduction strategy needs enough samples to measure its
+//! variance from, and gives a usable estimate once it has them.
+
+use first_library::{
+    estimate_pi_reduced, seeded_rng, try_estimate_pi_reduced, Error, VarianceReduction,
+};
+
+#[test]
+fn too_few_samples_are_rejected() {
+    for &method in VarianceReduction::ALL.iter() {
+        let mut rng = seeded_rng(1);
+        assert_eq!(
+            try_estimate_pi_reduced(0, method, &mut rng),
+            Err(Error::ZeroSamples)
+        );
+        for n in 1..method.min_samples() {
+            assert_eq!(
+                try_estimate_pi_reduced(n, method, &mut rng),
+                Err(Error::TooFewSamples),
+                "{} with {} samples",
+                method.name(),
+                n
+            );
+        }
+    }
+}
+
+#[test]
+fn fewest_samples_give_a_finite_estimate() {
+    for &method in VarianceReduction::ALL.iter() {
+        let mut rng = seeded_rng(1);
+        let estimate = try_estimate_pi_reduced(method.min_samples(), method, &mut rng).unwrap();
+        assert_eq!(estimate.samples, method.min_samples(), "{}", method.name());
+        assert!(estimate.pi.is_finite(), "{}", method.name());
+        assert!(estimate.variance.is_finite(), "{}", method.name());
+    }
+}
+
+#[test]
+#[should_panic(expected = "N must be at least 4 for VarianceReduction::Antithetic")]
+fn single_pair_panics() {
+    estimate_pi_reduced(2, VarianceReduction::Antithetic, &mut seeded_rng(1));
+}